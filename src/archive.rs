@@ -0,0 +1,179 @@
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::io::{Error, ErrorKind, Read};
+
+/// Metadata of a path inside a container, as returned by `Docker::stat_path`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[allow(non_snake_case)]
+pub struct PathStat {
+    pub name: String,
+    pub size: i64,
+    /// Go `os.FileMode` bits: permissions in the low bits, file type in the
+    /// high ones.
+    pub mode: u32,
+    pub mtime: String,
+    /// Target of the path if it is a symbolic link, empty otherwise.
+    pub linkTarget: String,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+const MODE_DIR: u32 = 1 << 31;
+const MODE_SYMLINK: u32 = 1 << 27;
+
+impl PathStat {
+    pub fn is_dir(&self) -> bool {
+        self.mode & MODE_DIR != 0
+    }
+
+    pub fn is_symlink(&self) -> bool {
+        self.mode & MODE_SYMLINK != 0
+    }
+
+    pub fn permissions(&self) -> u32 {
+        self.mode & 0o777
+    }
+
+    /// Decodes the `X-Docker-Container-Path-Stat` header, which holds the
+    /// stat as base64 encoded JSON.
+    pub(crate) fn from_header(header: &str) -> std::io::Result<PathStat> {
+        let json = decode_base64(header.trim())?;
+        match serde_json::from_slice(&json) {
+            Ok(stat) => Ok(stat),
+            Err(e) => Err(Error::new(ErrorKind::InvalidInput, e.to_string())),
+        }
+    }
+}
+
+/// Tar stream sent by the daemon, read as it arrives. It can be read as is,
+/// for instance to save it to a file, walked entry by entry, or taken back
+/// with `into_inner`.
+pub struct TarStream {
+    state: TarState,
+}
+
+/// The stream stays raw until `entries` is first called, and belongs to the
+/// archive walking it from then on.
+enum TarState {
+    Raw(Box<dyn Read + Send + Sync>),
+    Walking(tar::Archive<Box<dyn Read + Send + Sync>>),
+}
+
+impl TarStream {
+    pub(crate) fn new<R>(reader: R) -> Self
+    where
+        R: Read + Send + Sync + 'static,
+    {
+        TarStream {
+            state: TarState::Raw(Box::new(reader)),
+        }
+    }
+
+    /// Iterates over the entries of the archive. Each entry can be read for
+    /// its contents before moving to the next one.
+    pub fn entries(&mut self) -> std::io::Result<tar::Entries<'_, Box<dyn Read + Send + Sync>>> {
+        match self.state {
+            TarState::Walking(ref mut archive) => archive.entries(),
+            TarState::Raw(ref mut reader) => {
+                let reader = std::mem::replace(reader, Box::new(std::io::empty()));
+                self.state = TarState::Walking(tar::Archive::new(reader));
+                self.entries()
+            }
+        }
+    }
+
+    /// Returns the underlying reader, which must not have been walked yet
+    /// for the whole archive to be read.
+    pub fn into_inner(self) -> Box<dyn Read + Send + Sync> {
+        match self.state {
+            TarState::Raw(reader) => reader,
+            TarState::Walking(archive) => archive.into_inner(),
+        }
+    }
+
+    /// Returns the archive, whose `entries` can each be read for their
+    /// contents before moving to the next one.
+    pub fn into_archive(self) -> tar::Archive<Box<dyn Read + Send + Sync>> {
+        match self.state {
+            TarState::Raw(reader) => tar::Archive::new(reader),
+            TarState::Walking(archive) => archive,
+        }
+    }
+
+    /// Returns the contents of the file at `path`, relative to the root of
+    /// the archive, reading no further than that file.
+    pub fn read_file(mut self, path: &str) -> std::io::Result<Option<Vec<u8>>> {
+        let path = crate::pattern::clean(path);
+        for entry in self.entries()? {
+            let mut entry = entry?;
+            if crate::pattern::clean(&entry.path()?.to_string_lossy()) != path {
+                continue;
+            }
+            let mut contents = Vec::new();
+            entry.read_to_end(&mut contents)?;
+            return Ok(Some(contents));
+        }
+        Ok(None)
+    }
+
+    /// Adds up the size of the files in each directory of the archive,
+    /// including its subdirectories. The root directory is the empty path.
+    pub fn directory_sizes(mut self) -> std::io::Result<BTreeMap<String, u64>> {
+        let mut sizes = BTreeMap::new();
+        for entry in self.entries()? {
+            let entry = entry?;
+            let size = entry.header().size()?;
+            let path = crate::pattern::clean(&entry.path()?.to_string_lossy());
+            if entry.header().entry_type().is_dir() {
+                sizes.entry(path).or_insert(0);
+                continue;
+            }
+            *sizes.entry(String::new()).or_insert(0) += size;
+            for (i, _) in path.match_indices('/') {
+                *sizes.entry(path[..i].to_string()).or_insert(0) += size;
+            }
+        }
+        Ok(sizes)
+    }
+}
+
+impl Read for TarStream {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        match self.state {
+            TarState::Raw(ref mut reader) => reader.read(buf),
+            TarState::Walking(_) => Err(Error::other(
+                "tar stream is already being walked entry by entry",
+            )),
+        }
+    }
+}
+
+/// Decodes standard base64, the alphabet the daemon uses for the path stat.
+/// Decoding stops at the first `=`, so padding is optional.
+fn decode_base64(input: &str) -> std::io::Result<Vec<u8>> {
+    let mut output = Vec::with_capacity(input.len() * 3 / 4);
+    let mut bits = 0u32;
+    let mut count = 0;
+    for c in input.bytes().take_while(|&c| c != b'=') {
+        let value = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'a'..=b'z' => c - b'a' + 26,
+            b'0'..=b'9' => c - b'0' + 52,
+            b'+' => 62,
+            b'/' => 63,
+            _ => {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    format!("invalid base64 character {:?}", c as char),
+                ))
+            }
+        };
+        bits = (bits << 6) | value as u32;
+        count += 6;
+        if count >= 8 {
+            count -= 8;
+            output.push((bits >> count) as u8);
+        }
+    }
+    Ok(output)
+}
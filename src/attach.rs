@@ -0,0 +1,136 @@
+use crate::conn;
+use crate::stdio::{Demux, StdStream, StdinWriter};
+use crate::stream::CancelHandle;
+use std::io::{Error, Read};
+use std::sync::mpsc::{channel, Receiver, Sender};
+
+/// Which streams `Docker::attach` connects to.
+#[derive(Debug, Clone)]
+pub struct AttachOptions {
+    pub stdin: bool,
+    pub stdout: bool,
+    pub stderr: bool,
+    /// Replays the output the container already produced before streaming.
+    pub logs: bool,
+    /// Sequence that detaches from the container when written to stdin,
+    /// such as `ctrl-p,ctrl-q`. The daemon default is used if not given.
+    pub detach_keys: Option<String>,
+}
+
+impl Default for AttachOptions {
+    fn default() -> Self {
+        AttachOptions {
+            stdin: true,
+            stdout: true,
+            stderr: true,
+            logs: false,
+            detach_keys: None,
+        }
+    }
+}
+
+impl AttachOptions {
+    pub(crate) fn query(&self) -> String {
+        let mut query = format!(
+            "stream=1&stdin={}&stdout={}&stderr={}&logs={}",
+            self.stdin as u8, self.stdout as u8, self.stderr as u8, self.logs as u8
+        );
+        if let Some(keys) = &self.detach_keys {
+            query.push_str(&format!("&detachKeys={}", crate::docker::encode(keys)));
+        }
+        query
+    }
+}
+
+/// Streams of a container returned by `Docker::attach`.
+///
+/// The output is read off the connection by a background thread, so stdout
+/// and stderr can be read from separate threads. Output of a stream that is
+/// not read is kept in memory until the session is dropped.
+pub struct Attached {
+    pub stdin: StdinWriter,
+    pub stdout: OutputReader,
+    pub stderr: OutputReader,
+    handle: CancelHandle,
+}
+
+impl Attached {
+    pub(crate) fn new(body: conn::Body, tty: bool) -> std::io::Result<Self> {
+        let stdin = StdinWriter::new(body.stream())?;
+        let handle = CancelHandle::new(body.stream())?;
+        let (stdout_tx, stdout) = channel();
+        let (stderr_tx, stderr) = channel();
+        let cancelled = handle.clone();
+        std::thread::spawn(move || pump(Demux::new(body, tty), stdout_tx, stderr_tx, cancelled));
+
+        Ok(Attached {
+            stdin,
+            stdout: OutputReader::new(stdout),
+            stderr: OutputReader::new(stderr),
+            handle,
+        })
+    }
+
+    /// Returns a handle that closes the connection, detaching from the
+    /// container. The readers then end once the output received so far is
+    /// read.
+    pub fn cancel_handle(&self) -> CancelHandle {
+        self.handle.clone()
+    }
+}
+
+fn pump<R: Read>(
+    output: Demux<R>,
+    stdout: Sender<std::io::Result<Vec<u8>>>,
+    stderr: Sender<std::io::Result<Vec<u8>>>,
+    handle: CancelHandle,
+) {
+    for frame in output {
+        let _ = match frame {
+            Ok((StdStream::Stderr, data)) => stderr.send(Ok(data)),
+            Ok((_, data)) => stdout.send(Ok(data)),
+            Err(_) if handle.is_cancelled() => return,
+            Err(e) => {
+                let _ = stderr.send(Err(Error::new(e.kind(), e.to_string())));
+                stdout.send(Err(e))
+            }
+        };
+    }
+}
+
+/// Reads one of the output streams of an attached container, until the
+/// container exits or the session is detached.
+pub struct OutputReader {
+    chunks: Receiver<std::io::Result<Vec<u8>>>,
+    current: Vec<u8>,
+    position: usize,
+}
+
+impl OutputReader {
+    fn new(chunks: Receiver<std::io::Result<Vec<u8>>>) -> Self {
+        OutputReader {
+            chunks,
+            current: Vec::new(),
+            position: 0,
+        }
+    }
+}
+
+impl Read for OutputReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        while self.position == self.current.len() {
+            match self.chunks.recv() {
+                Ok(chunk) => {
+                    self.current = chunk?;
+                    self.position = 0;
+                }
+                // The connection is closed and every chunk was read.
+                Err(_) => return Ok(0),
+            }
+        }
+        let n = (self.current.len() - self.position).min(buf.len());
+        buf[..n].copy_from_slice(&self.current[self.position..self.position + n]);
+        self.position += n;
+        Ok(n)
+    }
+}
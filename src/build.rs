@@ -0,0 +1,241 @@
+use crate::image::ErrorDetail;
+use crate::pattern;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::fs;
+use std::io::{Error, ErrorKind, Read};
+use std::path::Path;
+
+#[derive(Debug, Clone, Default)]
+pub struct BuildOptions {
+    pub tags: Vec<String>,
+    pub dockerfile: Option<String>,
+    pub build_args: HashMap<String, String>,
+    pub labels: HashMap<String, String>,
+    pub target: Option<String>,
+    pub no_cache: bool,
+    pub pull: bool,
+    pub platform: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[allow(non_snake_case)]
+pub struct BuildMessage {
+    pub stream: Option<String>,
+    pub status: Option<String>,
+    pub id: Option<String>,
+    pub progress: Option<String>,
+    pub aux: Option<BuildAux>,
+    pub error: Option<String>,
+    pub errorDetail: Option<ErrorDetail>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[allow(non_snake_case)]
+pub struct BuildAux {
+    /// Set on the message announcing the built image.
+    pub ID: Option<String>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum BuildEvent {
+    /// A line of build output, such as `Step 1/3 : FROM alpine`.
+    Stream(String),
+    /// Progress of a layer being pulled for a base image.
+    Status {
+        id: Option<String>,
+        status: String,
+        progress: Option<String>,
+    },
+    /// The ID of the image that was built.
+    Image(String),
+    /// Auxiliary data other than the image ID, such as a pushed digest.
+    Aux(BuildAux),
+    /// The build failed with the given message.
+    Error(String),
+}
+
+impl From<BuildMessage> for BuildEvent {
+    fn from(message: BuildMessage) -> Self {
+        if let Some(detail) = message.errorDetail {
+            if let Some(error) = detail.message {
+                return BuildEvent::Error(error);
+            }
+        }
+        if let Some(error) = message.error {
+            return BuildEvent::Error(error);
+        }
+        if let Some(aux) = message.aux {
+            return match aux.ID {
+                Some(id) => BuildEvent::Image(id),
+                None => BuildEvent::Aux(aux),
+            };
+        }
+        if let Some(status) = message.status {
+            return BuildEvent::Status {
+                id: message.id,
+                status,
+                progress: message.progress,
+            };
+        }
+        BuildEvent::Stream(message.stream.unwrap_or_default())
+    }
+}
+
+/// Iterator over the events of a running build, as sent by the daemon.
+pub struct BuildStream<R: Read> {
+    messages: serde_json::StreamDeserializer<'static, serde_json::de::IoRead<R>, BuildMessage>,
+}
+
+impl<R: Read> BuildStream<R> {
+    pub(crate) fn new(reader: R) -> Self {
+        BuildStream {
+            messages: serde_json::Deserializer::from_reader(reader).into_iter(),
+        }
+    }
+}
+
+impl<R: Read> Iterator for BuildStream<R> {
+    type Item = std::io::Result<BuildEvent>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.messages.next()? {
+            Ok(message) => Some(Ok(BuildEvent::from(message))),
+            Err(e) => Some(Err(Error::new(ErrorKind::InvalidInput, e.to_string()))),
+        }
+    }
+}
+
+/// Packs `dir` into a tar archive usable as a build context, leaving out the
+/// files excluded by its `.dockerignore`.
+///
+/// `dockerfile` is the path of the Dockerfile relative to `dir`. It is always
+/// sent, as is the `.dockerignore` file itself, even when they are ignored.
+pub fn tar_context(dir: &Path, dockerfile: &str) -> std::io::Result<Vec<u8>> {
+    let ignore = match fs::read_to_string(dir.join(".dockerignore")) {
+        Ok(content) => parse_dockerignore(&content),
+        Err(e) if e.kind() == ErrorKind::NotFound => Vec::new(),
+        Err(e) => return Err(e),
+    };
+    let keep = [pattern::clean(dockerfile), ".dockerignore".to_string()];
+
+    let mut archive = tar::Builder::new(Vec::new());
+    archive.follow_symlinks(false);
+    append_dir(&mut archive, dir, "", &ignore, &keep)?;
+    archive.into_inner()
+}
+
+fn append_dir(
+    archive: &mut tar::Builder<Vec<u8>>,
+    dir: &Path,
+    prefix: &str,
+    ignore: &[(bool, String)],
+    keep: &[String],
+) -> std::io::Result<()> {
+    let mut entries = fs::read_dir(dir)?.collect::<std::io::Result<Vec<_>>>()?;
+    entries.sort_by_key(|entry| entry.file_name());
+
+    for entry in entries {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        let path = format!("{}{}", prefix, name);
+        let file_type = entry.file_type()?;
+        let excluded = is_excluded(ignore, &path) && !keep.contains(&path);
+
+        if file_type.is_dir() {
+            // An excluded directory may still hold files re-included by an
+            // exception pattern, or the Dockerfile, so only skip it when
+            // there are none.
+            let prefix = format!("{}/", path);
+            if excluded
+                && !ignore.iter().any(|(exclude, _)| !exclude)
+                && !keep.iter().any(|keep| keep.starts_with(&prefix))
+            {
+                continue;
+            }
+            if !excluded {
+                archive.append_dir(&path, entry.path())?;
+            }
+            append_dir(archive, &entry.path(), &prefix, ignore, keep)?;
+        } else if !excluded {
+            archive.append_path_with_name(entry.path(), &path)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Parses a `.dockerignore` file into `(exclude, pattern)` pairs, where
+/// `exclude` is false for `!` exception patterns.
+pub(crate) fn parse_dockerignore(content: &str) -> Vec<(bool, String)> {
+    content
+        .lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| match line.strip_prefix('!') {
+            Some(line) => (false, pattern::clean(line.trim())),
+            None => (true, pattern::clean(line)),
+        })
+        .collect()
+}
+
+/// A path is excluded when the last pattern matching it, or one of its parent
+/// directories, is not an exception.
+pub(crate) fn is_excluded(ignore: &[(bool, String)], path: &str) -> bool {
+    let mut excluded = false;
+    for (exclude, pattern) in ignore {
+        let mut parent = path;
+        let mut matched = pattern::matches(pattern, parent);
+        while !matched {
+            match parent.rfind('/') {
+                Some(i) => parent = &parent[..i],
+                None => break,
+            }
+            matched = pattern::matches(pattern, parent);
+        }
+        if matched {
+            excluded = *exclude;
+        }
+    }
+    excluded
+}
+
+pub(crate) fn query(options: &BuildOptions) -> String {
+    let mut query: Vec<(&str, String)> = Vec::new();
+    for tag in &options.tags {
+        query.push(("t", tag.clone()));
+    }
+    if let Some(dockerfile) = &options.dockerfile {
+        query.push(("dockerfile", dockerfile.clone()));
+    }
+    if !options.build_args.is_empty() {
+        query.push((
+            "buildargs",
+            serde_json::to_string(&options.build_args).unwrap(),
+        ));
+    }
+    if !options.labels.is_empty() {
+        query.push(("labels", serde_json::to_string(&options.labels).unwrap()));
+    }
+    if let Some(target) = &options.target {
+        query.push(("target", target.clone()));
+    }
+    if options.no_cache {
+        query.push(("nocache", "1".to_string()));
+    }
+    if options.pull {
+        query.push(("pull", "1".to_string()));
+    }
+    if let Some(platform) = &options.platform {
+        query.push(("platform", platform.clone()));
+    }
+
+    query
+        .iter()
+        .map(|(key, value)| format!("{}={}", key, crate::docker::encode(value)))
+        .collect::<Vec<String>>()
+        .join("&")
+}
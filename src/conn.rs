@@ -0,0 +1,270 @@
+//! Minimal HTTP/1.1 client over the daemon socket, for the calls isahc cannot
+//! serve: streams that must be cancellable from another thread, and
+//! connections the daemon hijacks for raw stdio.
+
+use crate::error::ApiError;
+use http::StatusCode;
+use std::io::{BufRead, BufReader, Error, ErrorKind, Read, Write};
+use std::os::unix::net::UnixStream;
+use std::path::Path;
+use std::sync::mpsc;
+use std::time::Duration;
+
+pub(crate) struct Response {
+    pub status: StatusCode,
+    pub headers: Vec<(String, String)>,
+    pub reader: BufReader<UnixStream>,
+}
+
+impl Response {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Turns the response into a reader over its body, decoding the chunked
+    /// transfer encoding if needed.
+    pub fn into_body(self) -> Body {
+        let chunked = self
+            .header("Transfer-Encoding")
+            .map(|value| value.eq_ignore_ascii_case("chunked"))
+            .unwrap_or(false);
+        let length = self
+            .header("Content-Length")
+            .and_then(|value| value.trim().parse().ok());
+        let kind = match (chunked, length) {
+            (true, _) => BodyKind::Chunked(0, false),
+            (false, Some(length)) => BodyKind::Length(length),
+            (false, None) => BodyKind::Close,
+        };
+        Body {
+            reader: self.reader,
+            kind,
+        }
+    }
+}
+
+/// Body of a request. A reader is sent with the chunked transfer encoding,
+/// as it is read.
+pub(crate) enum RequestBody<'a> {
+    Bytes(&'a [u8]),
+    Reader(&'a mut dyn Read),
+}
+
+/// Connects to the daemon socket. `UnixStream` cannot time out while
+/// connecting, so with a `timeout` the connection is made from another
+/// thread, which is left to finish on its own if it takes too long.
+pub(crate) fn connect(socket: &Path, timeout: Option<Duration>) -> std::io::Result<UnixStream> {
+    let timeout = match timeout {
+        Some(timeout) => timeout,
+        None => return UnixStream::connect(socket),
+    };
+    let (sender, receiver) = mpsc::channel();
+    let socket = socket.to_path_buf();
+    std::thread::spawn(move || {
+        let _ = sender.send(UnixStream::connect(socket));
+    });
+    match receiver.recv_timeout(timeout) {
+        Ok(stream) => stream,
+        Err(_) => Err(Error::new(
+            ErrorKind::TimedOut,
+            "timed out connecting to the daemon",
+        )),
+    }
+}
+
+/// Sends a request and reads the response head. Errors returned by the daemon
+/// are turned into an `ApiError`, as for every other call.
+///
+/// `timeout` bounds the time spent sending the request and waiting for the
+/// daemon to answer; reading the body afterwards is never timed out.
+pub(crate) fn request(
+    mut stream: UnixStream,
+    method: &str,
+    url: &str,
+    headers: &[(&str, &str)],
+    body: RequestBody,
+    timeout: Option<Duration>,
+) -> std::io::Result<Response> {
+    stream.set_read_timeout(timeout)?;
+    stream.set_write_timeout(timeout)?;
+
+    let mut head = format!("{} {} HTTP/1.1\r\nHost: localhost\r\n", method, url);
+    match body {
+        RequestBody::Bytes(bytes) => head.push_str(&format!("Content-Length: {}\r\n", bytes.len())),
+        RequestBody::Reader(_) => head.push_str("Transfer-Encoding: chunked\r\n"),
+    }
+    if !headers
+        .iter()
+        .any(|(name, _)| name.eq_ignore_ascii_case("Content-Type"))
+    {
+        head.push_str("Content-Type: application/json\r\n");
+    }
+    for (name, value) in headers {
+        head.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    head.push_str("\r\n");
+
+    let sent = stream
+        .write_all(head.as_bytes())
+        .and_then(|_| write_body(&mut stream, body));
+    let mut reader = BufReader::new(stream);
+    let (status, headers) = match (sent, read_head(&mut reader)) {
+        (Ok(_), head) => head?,
+        // The daemon may answer and hang up before reading the whole body,
+        // typically to refuse it, in which case its answer is what matters.
+        (Err(_), Ok((status, headers))) if !status.is_success() => (status, headers),
+        (Err(e), _) => return Err(timed_out(e)),
+    };
+
+    reader.get_ref().set_read_timeout(None)?;
+    reader.get_ref().set_write_timeout(None)?;
+
+    let response = Response {
+        status,
+        headers,
+        reader,
+    };
+    if !status.is_success() && status != StatusCode::SWITCHING_PROTOCOLS {
+        let mut body = String::new();
+        let _ = response.into_body().read_to_string(&mut body);
+        return Err(ApiError::new(status, &body).into());
+    }
+
+    Ok(response)
+}
+
+fn write_body(stream: &mut UnixStream, body: RequestBody) -> std::io::Result<()> {
+    let reader = match body {
+        RequestBody::Bytes(bytes) => return stream.write_all(bytes),
+        RequestBody::Reader(reader) => reader,
+    };
+    let mut buf = vec![0; 64 * 1024];
+    loop {
+        let n = match reader.read(&mut buf) {
+            Ok(n) => n,
+            Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        if n == 0 {
+            return stream.write_all(b"0\r\n\r\n");
+        }
+        stream.write_all(format!("{:x}\r\n", n).as_bytes())?;
+        stream.write_all(&buf[..n])?;
+        stream.write_all(b"\r\n")?;
+    }
+}
+
+/// Reads the status line and the headers of a response.
+fn read_head(
+    reader: &mut BufReader<UnixStream>,
+) -> std::io::Result<(StatusCode, Vec<(String, String)>)> {
+    let mut line = String::new();
+    if let Err(e) = reader.read_line(&mut line) {
+        return Err(timed_out(e));
+    }
+    let status = match line
+        .split_whitespace()
+        .nth(1)
+        .and_then(|code| StatusCode::from_bytes(code.as_bytes()).ok())
+    {
+        Some(status) => status,
+        None => return Err(invalid_data(format!("malformed status line: {}", line))),
+    };
+
+    let mut headers = Vec::new();
+    loop {
+        line.clear();
+        if reader.read_line(&mut line).map_err(timed_out)? == 0 {
+            return Err(Error::new(ErrorKind::UnexpectedEof, "connection closed"));
+        }
+        let header = line.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            headers.push((name.trim().to_string(), value.trim().to_string()));
+        }
+    }
+    Ok((status, headers))
+}
+
+/// Socket timeouts surface as `WouldBlock`, which says little about what
+/// happened.
+fn timed_out(e: Error) -> Error {
+    match e.kind() {
+        ErrorKind::WouldBlock => Error::new(ErrorKind::TimedOut, "timed out"),
+        _ => e,
+    }
+}
+
+enum BodyKind {
+    /// Bytes left in the current chunk, and whether the last one was read.
+    Chunked(usize, bool),
+    Length(usize),
+    Close,
+}
+
+pub(crate) struct Body {
+    reader: BufReader<UnixStream>,
+    kind: BodyKind,
+}
+
+impl Body {
+    pub fn stream(&self) -> &UnixStream {
+        self.reader.get_ref()
+    }
+}
+
+impl Read for Body {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        match self.kind {
+            BodyKind::Close => self.reader.read(buf),
+            BodyKind::Length(0) => Ok(0),
+            BodyKind::Length(remaining) => {
+                let max = remaining.min(buf.len());
+                let n = self.reader.read(&mut buf[..max])?;
+                self.kind = BodyKind::Length(remaining - n);
+                Ok(n)
+            }
+            BodyKind::Chunked(_, true) => Ok(0),
+            BodyKind::Chunked(0, false) => {
+                let mut line = String::new();
+                // Every chunk but the first is preceded by the CRLF that ends
+                // the previous one.
+                while line.trim().is_empty() {
+                    line.clear();
+                    if self.reader.read_line(&mut line)? == 0 {
+                        return Ok(0);
+                    }
+                }
+                let size = line.trim().split(';').next().unwrap_or("");
+                let size = match usize::from_str_radix(size, 16) {
+                    Ok(size) => size,
+                    Err(_) => return Err(invalid_data(format!("malformed chunk size: {}", size))),
+                };
+                if size == 0 {
+                    self.kind = BodyKind::Chunked(0, true);
+                    return Ok(0);
+                }
+                self.kind = BodyKind::Chunked(size, false);
+                self.read(buf)
+            }
+            BodyKind::Chunked(remaining, false) => {
+                let max = remaining.min(buf.len());
+                let n = self.reader.read(&mut buf[..max])?;
+                if n == 0 {
+                    return Err(Error::new(ErrorKind::UnexpectedEof, "truncated chunk"));
+                }
+                self.kind = BodyKind::Chunked(remaining - n, false);
+                Ok(n)
+            }
+        }
+    }
+}
+
+fn invalid_data(message: String) -> Error {
+    Error::new(ErrorKind::InvalidData, message)
+}
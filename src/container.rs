@@ -1,4 +1,8 @@
+use crate::error::invalid;
+use crate::network::{EndpointConfig, EndpointSettings};
+use serde_json::{Map, Value};
 use std::collections::HashMap;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 #[derive(Serialize, Deserialize, Debug)]
 #[allow(non_snake_case)]
@@ -6,15 +10,113 @@ use std::collections::HashMap;
 pub struct Container {
     pub Id: String,
     pub Image: String,
+    pub ImageID: Option<String>,
+    /// Human readable, such as `Up 3 minutes (healthy)`; see `State` for
+    /// something to match on.
     pub Status: String,
+    /// Not sent by old daemons.
+    pub State: Option<ContainerState>,
     pub Command: String,
+    /// Unix timestamp, in seconds; see `created_at`.
     pub Created: u64,
     pub Names: Vec<String>,
     pub Ports: Vec<Port>,
     pub SizeRw: Option<u64>, // I guess it is optional on Mac.
-    pub SizeRootFs: u64,
+    pub SizeRootFs: Option<u64>,
     pub Labels: Option<HashMap<String, String>>,
     pub HostConfig: HostConfig,
+    pub Mounts: Option<Vec<MountPoint>>,
+    pub NetworkSettings: Option<SummaryNetworkSettings>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(from = "String", into = "String")]
+pub enum ContainerState {
+    Created,
+    Running,
+    Paused,
+    Restarting,
+    Removing,
+    Exited,
+    Dead,
+    /// A state this crate does not know about.
+    Unknown(String),
+}
+
+impl ContainerState {
+    pub fn as_str(&self) -> &str {
+        match self {
+            ContainerState::Created => "created",
+            ContainerState::Running => "running",
+            ContainerState::Paused => "paused",
+            ContainerState::Restarting => "restarting",
+            ContainerState::Removing => "removing",
+            ContainerState::Exited => "exited",
+            ContainerState::Dead => "dead",
+            ContainerState::Unknown(state) => state,
+        }
+    }
+
+    /// Paused containers count as running, as their processes still exist.
+    pub fn is_running(&self) -> bool {
+        *self == ContainerState::Running || *self == ContainerState::Paused
+    }
+}
+
+impl From<String> for ContainerState {
+    fn from(state: String) -> Self {
+        match state.as_str() {
+            "created" => ContainerState::Created,
+            "running" => ContainerState::Running,
+            "paused" => ContainerState::Paused,
+            "restarting" => ContainerState::Restarting,
+            "removing" => ContainerState::Removing,
+            "exited" => ContainerState::Exited,
+            "dead" => ContainerState::Dead,
+            _ => ContainerState::Unknown(state),
+        }
+    }
+}
+
+impl From<ContainerState> for String {
+    fn from(state: ContainerState) -> Self {
+        state.as_str().to_string()
+    }
+}
+
+impl std::fmt::Display for ContainerState {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::result::Result<(), std::fmt::Error> {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// A volume or bind mount of a container.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[allow(non_snake_case)]
+pub struct MountPoint {
+    /// One of `bind`, `volume`, `tmpfs` or `npipe`.
+    pub Type: String,
+    /// Name of the volume, for volume mounts.
+    pub Name: Option<String>,
+    pub Source: String,
+    pub Destination: String,
+    pub Driver: Option<String>,
+    pub Mode: String,
+    pub RW: bool,
+    pub Propagation: String,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[allow(non_snake_case)]
+pub struct SummaryNetworkSettings {
+    /// Endpoints of the container, by network name.
+    pub Networks: HashMap<String, EndpointSettings>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -24,12 +126,16 @@ pub struct Port {
     pub PrivatePort: u64,
     pub PublicPort: Option<u64>,
     pub Type: String,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 #[allow(non_snake_case)]
 pub struct HostConfig {
     pub NetworkMode: String,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -40,9 +146,9 @@ pub struct ContainerInfo {
     // Config
     pub Created: String,
     pub Driver: String,
-    pub ExecDriver: String,
+    /// Only sent by old daemons.
+    pub ExecDriver: Option<String>,
     // ExecIDs
-    // HostConfig
     pub HostnamePath: String,
     pub HostsPath: String,
     pub LogPath: String,
@@ -55,9 +161,118 @@ pub struct ContainerInfo {
     pub ProcessLabel: String,
     pub ResolvConfPath: String,
     pub RestartCount: u64,
-    // State
-    pub Volumes: HashMap<String, String>,
-    pub VolumesRW: HashMap<String, bool>,
+    pub HostConfig: Option<ContainerHostConfig>,
+    pub State: State,
+    /// Only sent by old daemons, see `Mounts` in `extra` otherwise.
+    pub Volumes: Option<HashMap<String, String>>,
+    pub VolumesRW: Option<HashMap<String, bool>>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// State of a container, as returned by inspect.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[allow(non_snake_case)]
+pub struct State {
+    /// Such as `running` or `exited`, not sent by old daemons.
+    pub Status: Option<String>,
+    pub Running: bool,
+    pub Paused: bool,
+    pub Restarting: bool,
+    pub OOMKilled: bool,
+    pub Dead: bool,
+    pub Pid: i64,
+    pub ExitCode: i64,
+    pub Error: String,
+    pub StartedAt: String,
+    pub FinishedAt: String,
+    /// Only set for containers with a healthcheck.
+    pub Health: Option<Health>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[allow(non_snake_case)]
+pub struct Health {
+    pub Status: HealthStatus,
+    /// Number of consecutive failed healthchecks.
+    pub FailingStreak: i64,
+    /// Most recent healthcheck runs, oldest first.
+    pub Log: Option<Vec<HealthLog>>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl Health {
+    pub fn is_healthy(&self) -> bool {
+        self.Status == HealthStatus::Healthy
+    }
+
+    /// Returns up to the `n` most recent healthcheck runs, oldest first.
+    pub fn last_probes(&self, n: usize) -> &[HealthLog] {
+        let log = self.Log.as_deref().unwrap_or(&[]);
+        &log[log.len().saturating_sub(n)..]
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(from = "String", into = "String")]
+pub enum HealthStatus {
+    /// The container has no healthcheck.
+    None,
+    Starting,
+    Healthy,
+    Unhealthy,
+    /// A status this crate does not know about.
+    Unknown(String),
+}
+
+impl HealthStatus {
+    pub fn as_str(&self) -> &str {
+        match self {
+            HealthStatus::None => "none",
+            HealthStatus::Starting => "starting",
+            HealthStatus::Healthy => "healthy",
+            HealthStatus::Unhealthy => "unhealthy",
+            HealthStatus::Unknown(status) => status,
+        }
+    }
+}
+
+impl From<String> for HealthStatus {
+    fn from(status: String) -> Self {
+        match status.as_str() {
+            "none" => HealthStatus::None,
+            "starting" => HealthStatus::Starting,
+            "healthy" => HealthStatus::Healthy,
+            "unhealthy" => HealthStatus::Unhealthy,
+            _ => HealthStatus::Unknown(status),
+        }
+    }
+}
+
+impl From<HealthStatus> for String {
+    fn from(status: HealthStatus) -> Self {
+        status.as_str().to_string()
+    }
+}
+
+impl std::fmt::Display for HealthStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::result::Result<(), std::fmt::Error> {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[allow(non_snake_case)]
+pub struct HealthLog {
+    pub Start: String,
+    pub End: String,
+    pub ExitCode: i64,
+    pub Output: String,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
 }
 
 impl Clone for Container {
@@ -65,7 +280,9 @@ impl Clone for Container {
         Container {
             Id: self.Id.clone(),
             Image: self.Image.clone(),
+            ImageID: self.ImageID.clone(),
             Status: self.Status.clone(),
+            State: self.State.clone(),
             Command: self.Command.clone(),
             Created: self.Created,
             Names: self.Names.clone(),
@@ -74,8 +291,26 @@ impl Clone for Container {
             SizeRootFs: self.SizeRootFs,
             Labels: self.Labels.clone(),
             HostConfig: self.HostConfig.clone(),
+            Mounts: self.Mounts.clone(),
+            NetworkSettings: self.NetworkSettings.clone(),
+            extra: self.extra.clone(),
+        }
+    }
+}
+
+impl Container {
+    /// Whether the container is running, paused or not. Old daemons that do
+    /// not send `State` fall back on `Status`.
+    pub fn is_running(&self) -> bool {
+        match &self.State {
+            Some(state) => state.is_running(),
+            None => self.Status.starts_with("Up"),
         }
     }
+
+    pub fn created_at(&self) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(self.Created)
+    }
 }
 
 impl std::fmt::Display for Container {
@@ -91,6 +326,7 @@ impl std::clone::Clone for Port {
             PrivatePort: self.PrivatePort,
             PublicPort: self.PublicPort,
             Type: self.Type.clone(),
+            extra: self.extra.clone(),
         }
     }
 }
@@ -99,6 +335,7 @@ impl Clone for HostConfig {
     fn clone(&self) -> Self {
         HostConfig {
             NetworkMode: self.NetworkMode.clone(),
+            extra: self.extra.clone(),
         }
     }
 }
@@ -113,7 +350,6 @@ impl Clone for ContainerInfo {
             Driver: self.Driver.clone(),
             ExecDriver: self.ExecDriver.clone(),
             // ExecIDs
-            // HostConfig
             HostnamePath: self.HostnamePath.clone(),
             HostsPath: self.HostsPath.clone(),
             LogPath: self.LogPath.clone(),
@@ -126,9 +362,11 @@ impl Clone for ContainerInfo {
             ProcessLabel: self.ProcessLabel.clone(),
             ResolvConfPath: self.ResolvConfPath.clone(),
             RestartCount: self.RestartCount,
-            // State
+            HostConfig: self.HostConfig.clone(),
+            State: self.State.clone(),
             Volumes: self.Volumes.clone(),
             VolumesRW: self.VolumesRW.clone(),
+            extra: self.extra.clone(),
         }
     }
 }
@@ -139,6 +377,150 @@ impl std::fmt::Display for ContainerInfo {
     }
 }
 
+/// Configuration of a container, as returned by inspect. Limits set to 0
+/// are unlimited, or left to the daemon default.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[allow(non_snake_case)]
+pub struct ContainerHostConfig {
+    pub NetworkMode: Option<String>,
+    pub RestartPolicy: Option<RestartPolicy>,
+    pub CpuShares: Option<i64>,
+    pub CpuQuota: Option<i64>,
+    pub CpuPeriod: Option<i64>,
+    pub NanoCpus: Option<i64>,
+    pub CpusetCpus: Option<String>,
+    pub CpusetMems: Option<String>,
+    pub Memory: Option<i64>,
+    pub MemorySwap: Option<i64>,
+    pub MemoryReservation: Option<i64>,
+    pub PidsLimit: Option<i64>,
+    pub BlkioWeight: Option<u16>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl ContainerHostConfig {
+    /// Returns the limits in effect, in the form `update_container` takes
+    /// them, so they can be compared with an update or restored later.
+    /// Limits the daemon reports as unset, with a zero or an empty value, and
+    /// the `no` restart policy are left to `None`, so that an update built
+    /// from them only carries the limits that were actually set.
+    pub fn resources(&self) -> ResourceUpdate {
+        fn set<T: Default + PartialEq + Clone>(value: &Option<T>) -> Option<T> {
+            value.clone().filter(|v| *v != T::default())
+        }
+
+        ResourceUpdate {
+            CpuShares: set(&self.CpuShares),
+            CpuQuota: set(&self.CpuQuota),
+            CpuPeriod: set(&self.CpuPeriod),
+            NanoCpus: set(&self.NanoCpus),
+            CpusetCpus: set(&self.CpusetCpus),
+            CpusetMems: set(&self.CpusetMems),
+            Memory: set(&self.Memory),
+            MemorySwap: set(&self.MemorySwap),
+            MemoryReservation: set(&self.MemoryReservation),
+            PidsLimit: set(&self.PidsLimit),
+            BlkioWeight: set(&self.BlkioWeight),
+            RestartPolicy: self
+                .RestartPolicy
+                .clone()
+                .filter(|policy| !policy.Name.is_empty() && policy.Name != "no"),
+        }
+    }
+}
+
+/// Limits changed by `update_container`. Fields left to `None` are kept as
+/// they are.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[allow(non_snake_case)]
+pub struct ResourceUpdate {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub CpuShares: Option<i64>,
+    /// Microseconds of CPU time per `CpuPeriod`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub CpuQuota: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub CpuPeriod: Option<i64>,
+    /// Number of CPUs, in billionths of a CPU.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub NanoCpus: Option<i64>,
+    /// CPUs the container may run on, such as `0-3` or `0,1`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub CpusetCpus: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub CpusetMems: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Memory: Option<i64>,
+    /// Memory plus swap, or -1 for unlimited swap.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub MemorySwap: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub MemoryReservation: Option<i64>,
+    /// Use -1 to remove the limit.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub PidsLimit: Option<i64>,
+    /// Between 10 and 1000.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub BlkioWeight: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub RestartPolicy: Option<RestartPolicy>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct ContainerUpdated {
+    #[serde(rename = "Warnings")]
+    pub warnings: Option<Vec<String>>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ContainerListOptions {
+    /// Also lists the containers that are not running.
+    pub all: bool,
+    /// Returns only the most recently created containers, stopped or not.
+    pub limit: Option<u64>,
+    /// Computes `SizeRw` and `SizeRootFs`, which is slow.
+    pub size: bool,
+    pub id: Vec<String>,
+    pub name: Vec<String>,
+    /// Labels given as `key` or `key=value`.
+    pub label: Vec<String>,
+    /// Images the containers were created from, by name or ID.
+    pub ancestor: Vec<String>,
+    /// States such as `running` or `exited`.
+    pub status: Vec<String>,
+    pub health: Vec<HealthStatus>,
+    pub network: Vec<String>,
+    pub volume: Vec<String>,
+}
+
+impl ContainerListOptions {
+    pub(crate) fn to_map(&self) -> HashMap<String, Vec<String>> {
+        let mut filters = HashMap::new();
+        let lists = [
+            ("id", &self.id),
+            ("name", &self.name),
+            ("label", &self.label),
+            ("ancestor", &self.ancestor),
+            ("status", &self.status),
+            ("network", &self.network),
+            ("volume", &self.volume),
+        ];
+        for (name, values) in lists.iter() {
+            if !values.is_empty() {
+                filters.insert(name.to_string(), values.to_vec());
+            }
+        }
+        if !self.health.is_empty() {
+            let health = self.health.iter().map(|h| h.as_str().to_string()).collect();
+            filters.insert("health".to_string(), health);
+        }
+        filters
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 #[allow(non_snake_case)]
 pub struct PortBinding {
@@ -161,12 +543,43 @@ impl std::fmt::Display for PortBinding {
     }
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Default)]
 #[allow(non_snake_case)]
 pub struct HostConfigCreate {
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub NetworkMode: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub PublishAllPorts: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub PortBindings: Option<HashMap<String, Vec<PortBinding>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Binds: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Mounts: Option<Vec<Mount>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Memory: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub MemorySwap: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub MemoryReservation: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub CpuShares: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub CpuQuota: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub CpuPeriod: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub NanoCpus: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub PidsLimit: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub RestartPolicy: Option<RestartPolicy>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub CapAdd: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub CapDrop: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Privileged: Option<bool>,
 }
 
 impl Clone for HostConfigCreate {
@@ -175,6 +588,20 @@ impl Clone for HostConfigCreate {
             NetworkMode: self.NetworkMode.clone(),
             PublishAllPorts: self.PublishAllPorts,
             PortBindings: self.PortBindings.clone(),
+            Binds: self.Binds.clone(),
+            Mounts: self.Mounts.clone(),
+            Memory: self.Memory,
+            MemorySwap: self.MemorySwap,
+            MemoryReservation: self.MemoryReservation,
+            CpuShares: self.CpuShares,
+            CpuQuota: self.CpuQuota,
+            CpuPeriod: self.CpuPeriod,
+            NanoCpus: self.NanoCpus,
+            PidsLimit: self.PidsLimit,
+            RestartPolicy: self.RestartPolicy.clone(),
+            CapAdd: self.CapAdd.clone(),
+            CapDrop: self.CapDrop.clone(),
+            Privileged: self.Privileged,
         }
     }
 }
@@ -185,13 +612,79 @@ impl std::fmt::Display for HostConfigCreate {
     }
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[allow(non_snake_case)]
+pub struct Mount {
+    pub Target: String,
+    pub Source: Option<String>,
+    /// One of `bind`, `volume`, `tmpfs` or `npipe`.
+    pub Type: String,
+    pub ReadOnly: Option<bool>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[allow(non_snake_case)]
+pub struct RestartPolicy {
+    /// One of `no`, `always`, `unless-stopped` or `on-failure`.
+    pub Name: String,
+    pub MaximumRetryCount: Option<i64>,
+}
+
+/// Durations are in nanoseconds.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[allow(non_snake_case)]
+pub struct Healthcheck {
+    /// Such as `["CMD-SHELL", "pg_isready"]`, or `["NONE"]` to disable the
+    /// healthcheck inherited from the image.
+    pub Test: Vec<String>,
+    pub Interval: Option<i64>,
+    pub Timeout: Option<i64>,
+    pub Retries: Option<i64>,
+    pub StartPeriod: Option<i64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[allow(non_snake_case)]
+pub struct NetworkingConfig {
+    pub EndpointsConfig: HashMap<String, EndpointConfig>,
+}
+
+/// Serialized as `{}`, the value the API expects in port and volume sets.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct Empty {}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
 #[allow(non_snake_case)]
 pub struct ContainerCreate {
     pub Image: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub Labels: Option<HashMap<String, String>>,
-    pub ExposedPorts: Option<HashMap<String, HashMap<i32, i32>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ExposedPorts: Option<HashMap<String, Empty>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub HostConfig: Option<HostConfigCreate>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Cmd: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Entrypoint: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Env: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub WorkingDir: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub User: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Tty: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub OpenStdin: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Healthcheck: Option<Healthcheck>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub StopSignal: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub StopTimeout: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub NetworkingConfig: Option<NetworkingConfig>,
 }
 
 impl Clone for ContainerCreate {
@@ -201,6 +694,17 @@ impl Clone for ContainerCreate {
             Labels: self.Labels.clone(),
             ExposedPorts: self.ExposedPorts.clone(),
             HostConfig: self.HostConfig.clone(),
+            Cmd: self.Cmd.clone(),
+            Entrypoint: self.Entrypoint.clone(),
+            Env: self.Env.clone(),
+            WorkingDir: self.WorkingDir.clone(),
+            User: self.User.clone(),
+            Tty: self.Tty,
+            OpenStdin: self.OpenStdin,
+            Healthcheck: self.Healthcheck.clone(),
+            StopSignal: self.StopSignal.clone(),
+            StopTimeout: self.StopTimeout,
+            NetworkingConfig: self.NetworkingConfig.clone(),
         }
     }
 }
@@ -210,3 +714,309 @@ impl std::fmt::Display for ContainerCreate {
         write!(f, "{}", self.Image)
     }
 }
+
+impl ContainerCreate {
+    pub fn builder(image: &str) -> ContainerCreateBuilder {
+        ContainerCreateBuilder {
+            container: ContainerCreate {
+                Image: image.to_string(),
+                ..Default::default()
+            },
+            ports: Vec::new(),
+        }
+    }
+}
+
+pub struct ContainerCreateBuilder {
+    container: ContainerCreate,
+    /// Port specs as given, checked and normalized by `build`.
+    ports: Vec<(String, Option<PortBinding>)>,
+}
+
+impl ContainerCreateBuilder {
+    pub fn cmd(mut self, cmd: &[&str]) -> Self {
+        self.container.Cmd = Some(cmd.iter().map(|s| s.to_string()).collect());
+        self
+    }
+
+    pub fn entrypoint(mut self, entrypoint: &[&str]) -> Self {
+        self.container.Entrypoint = Some(entrypoint.iter().map(|s| s.to_string()).collect());
+        self
+    }
+
+    pub fn env(mut self, key: &str, value: &str) -> Self {
+        self.container
+            .Env
+            .get_or_insert_with(Vec::new)
+            .push(format!("{}={}", key, value));
+        self
+    }
+
+    pub fn working_dir(mut self, working_dir: &str) -> Self {
+        self.container.WorkingDir = Some(working_dir.to_string());
+        self
+    }
+
+    pub fn user(mut self, user: &str) -> Self {
+        self.container.User = Some(user.to_string());
+        self
+    }
+
+    pub fn tty(mut self, tty: bool) -> Self {
+        self.container.Tty = Some(tty);
+        self
+    }
+
+    pub fn open_stdin(mut self, open_stdin: bool) -> Self {
+        self.container.OpenStdin = Some(open_stdin);
+        self
+    }
+
+    pub fn label(mut self, key: &str, value: &str) -> Self {
+        self.container
+            .Labels
+            .get_or_insert_with(HashMap::new)
+            .insert(key.to_string(), value.to_string());
+        self
+    }
+
+    pub fn healthcheck(mut self, healthcheck: Healthcheck) -> Self {
+        self.container.Healthcheck = Some(healthcheck);
+        self
+    }
+
+    pub fn stop_signal(mut self, signal: &str) -> Self {
+        self.container.StopSignal = Some(signal.to_string());
+        self
+    }
+
+    pub fn stop_timeout(mut self, seconds: i64) -> Self {
+        self.container.StopTimeout = Some(seconds);
+        self
+    }
+
+    /// Exposes a port given as `port[/protocol]`, such as `80/tcp`, or a
+    /// range of ports such as `8000-8010/udp`.
+    pub fn expose(mut self, port: &str) -> Self {
+        self.ports.push((port.to_string(), None));
+        self
+    }
+
+    /// Exposes a port and publishes it on `host_port` of the host. A range of
+    /// ports is published on a host range of the same size.
+    pub fn publish(mut self, port: &str, host_ip: Option<&str>, host_port: &str) -> Self {
+        let binding = PortBinding {
+            HostIp: host_ip.map(|ip| ip.to_string()),
+            HostPort: host_port.to_string(),
+        };
+        self.ports.push((port.to_string(), Some(binding)));
+        self
+    }
+
+    pub fn publish_all_ports(mut self, publish_all_ports: bool) -> Self {
+        self.host_config().PublishAllPorts = Some(publish_all_ports);
+        self
+    }
+
+    /// Adds a bind mount given as `source:target[:options]`.
+    pub fn bind(mut self, bind: &str) -> Self {
+        self.host_config()
+            .Binds
+            .get_or_insert_with(Vec::new)
+            .push(bind.to_string());
+        self
+    }
+
+    pub fn mount(mut self, mount: Mount) -> Self {
+        self.host_config()
+            .Mounts
+            .get_or_insert_with(Vec::new)
+            .push(mount);
+        self
+    }
+
+    pub fn memory(mut self, bytes: i64) -> Self {
+        self.host_config().Memory = Some(bytes);
+        self
+    }
+
+    pub fn memory_swap(mut self, bytes: i64) -> Self {
+        self.host_config().MemorySwap = Some(bytes);
+        self
+    }
+
+    pub fn memory_reservation(mut self, bytes: i64) -> Self {
+        self.host_config().MemoryReservation = Some(bytes);
+        self
+    }
+
+    pub fn cpu_shares(mut self, shares: i64) -> Self {
+        self.host_config().CpuShares = Some(shares);
+        self
+    }
+
+    pub fn cpu_quota(mut self, quota: i64) -> Self {
+        self.host_config().CpuQuota = Some(quota);
+        self
+    }
+
+    pub fn cpu_period(mut self, period: i64) -> Self {
+        self.host_config().CpuPeriod = Some(period);
+        self
+    }
+
+    /// Limits the container to a number of CPUs, in billionths of a CPU.
+    pub fn nano_cpus(mut self, nano_cpus: i64) -> Self {
+        self.host_config().NanoCpus = Some(nano_cpus);
+        self
+    }
+
+    pub fn pids_limit(mut self, limit: i64) -> Self {
+        self.host_config().PidsLimit = Some(limit);
+        self
+    }
+
+    pub fn restart_policy(mut self, name: &str, maximum_retry_count: Option<i64>) -> Self {
+        self.host_config().RestartPolicy = Some(RestartPolicy {
+            Name: name.to_string(),
+            MaximumRetryCount: maximum_retry_count,
+        });
+        self
+    }
+
+    pub fn cap_add(mut self, capability: &str) -> Self {
+        self.host_config()
+            .CapAdd
+            .get_or_insert_with(Vec::new)
+            .push(capability.to_string());
+        self
+    }
+
+    pub fn cap_drop(mut self, capability: &str) -> Self {
+        self.host_config()
+            .CapDrop
+            .get_or_insert_with(Vec::new)
+            .push(capability.to_string());
+        self
+    }
+
+    pub fn privileged(mut self, privileged: bool) -> Self {
+        self.host_config().Privileged = Some(privileged);
+        self
+    }
+
+    pub fn network_mode(mut self, network_mode: &str) -> Self {
+        self.host_config().NetworkMode = Some(network_mode.to_string());
+        self
+    }
+
+    /// Attaches the container to a network when it is created.
+    pub fn network(mut self, network: &str, endpoint: EndpointConfig) -> Self {
+        self.container
+            .NetworkingConfig
+            .get_or_insert_with(NetworkingConfig::default)
+            .EndpointsConfig
+            .insert(network.to_string(), endpoint);
+        self
+    }
+
+    /// Checks the exposed and published port specs before the request is
+    /// sent, so that a typo does not surface as a daemon error.
+    pub fn build(mut self) -> std::io::Result<ContainerCreate> {
+        if self.container.Image.is_empty() {
+            return Err(invalid("image must not be empty".to_string()));
+        }
+
+        for (spec, binding) in std::mem::take(&mut self.ports) {
+            let (start, end, protocol) = parse_port(&spec)?;
+            // As the CLI does, a range is expanded to one key per port, since
+            // the daemon does not accept ranges as keys.
+            let host_ports = match &binding {
+                Some(binding) if !binding.HostPort.is_empty() => {
+                    let (host_start, host_end) = parse_port_range(&binding.HostPort)?;
+                    if host_end - host_start == end - start {
+                        Some((host_start, true))
+                    } else if start == end {
+                        // A single port may be published on any port of a
+                        // host range, which the daemon picks.
+                        Some((host_start, false))
+                    } else {
+                        return Err(invalid(format!(
+                            "{}: the host port range {} does not match",
+                            spec, binding.HostPort
+                        )));
+                    }
+                }
+                _ => None,
+            };
+
+            for port in start..=end {
+                let key = format!("{}/{}", port, protocol);
+                self.container
+                    .ExposedPorts
+                    .get_or_insert_with(HashMap::new)
+                    .insert(key.clone(), Empty {});
+                let mut binding = match &binding {
+                    Some(binding) => binding.clone(),
+                    None => continue,
+                };
+                if let Some((host_start, true)) = host_ports {
+                    binding.HostPort = (host_start + (port - start)).to_string();
+                }
+                self.host_config()
+                    .PortBindings
+                    .get_or_insert_with(HashMap::new)
+                    .entry(key)
+                    .or_default()
+                    .push(binding);
+            }
+        }
+
+        Ok(self.container)
+    }
+
+    fn host_config(&mut self) -> &mut HostConfigCreate {
+        self.container
+            .HostConfig
+            .get_or_insert_with(HostConfigCreate::default)
+    }
+}
+
+/// Parses a port spec such as `80`, `53/udp` or `8000-8010/tcp` into its
+/// first and last port and its protocol.
+fn parse_port(spec: &str) -> std::io::Result<(u16, u16, &str)> {
+    let (ports, protocol) = match spec.split_once('/') {
+        Some((ports, protocol)) => (ports, protocol),
+        None => (spec, "tcp"),
+    };
+    match protocol {
+        "tcp" | "udp" | "sctp" => {}
+        _ => return Err(invalid(format!("{}: unknown protocol {}", spec, protocol))),
+    }
+    let (start, end) = parse_port_range(ports)?;
+    Ok((start, end, protocol))
+}
+
+fn parse_port_range(ports: &str) -> std::io::Result<(u16, u16)> {
+    let (start, end) = match ports.split_once('-') {
+        Some((start, end)) => (start, end),
+        None => (ports, ports),
+    };
+    match (start.parse::<u16>(), end.parse::<u16>()) {
+        (Ok(start), Ok(end)) if start > 0 && start <= end => Ok((start, end)),
+        _ => Err(invalid(format!(
+            "{} is not a valid port or port range",
+            ports
+        ))),
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct ContainerCreated {
+    #[serde(rename = "Id")]
+    pub id: String,
+    #[serde(rename = "Warnings", default)]
+    pub warnings: Option<Vec<String>>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
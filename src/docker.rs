@@ -1,25 +1,62 @@
-use crate::container::{Container, ContainerInfo};
+use crate::archive::{PathStat, TarStream};
+use crate::attach::{AttachOptions, Attached};
+use crate::build::{self, BuildOptions, BuildStream};
+use crate::conn::{self, RequestBody};
+use crate::container::{
+    Container, ContainerCreate, ContainerCreated, ContainerInfo, ContainerListOptions,
+    ContainerUpdated, Health, ResourceUpdate,
+};
+use crate::error::ApiError;
 use crate::event::Event;
+use crate::exec::{ExecCreated, ExecInspect, ExecOptions, ExecOutput, ExecSession};
+use crate::extra::Extra;
 use crate::filesystem::FilesystemChange;
-use crate::image::Image;
-use crate::network::{Network, NetworkCreate};
+use crate::image::{Image, ImageLoad, ImageSearchFilters, ImageSearchResult};
+use crate::network::{
+    EndpointConfig, Network, NetworkConnect, NetworkCreate, NetworkCreated, NetworkDisconnect,
+    NetworkListOptions, NetworkPrune, NetworkPruneFilters,
+};
 use crate::process::{Process, Top};
+use crate::raw::{RawBody, RawResponse};
+use crate::retry::{RetryAttempt, RetryPolicy};
 use crate::stats::Stats;
-use crate::system::SystemInfo;
+use crate::stream::JsonStream;
+use crate::system::{DiskUsage, SystemInfo};
 use crate::version::Version;
+use crate::volume::{
+    Volume, VolumeCreate, VolumeList, VolumeListOptions, VolumePrune, VolumePruneFilters,
+};
+use crate::wait::{HealthTimeout, WaitCondition, WaitResponse};
+use http::header::{HeaderName, HeaderValue, ACCEPT, CONTENT_TYPE};
 use http::method::Method;
-use isahc::{config::Dialer, prelude::*, send, Body, Request};
+use isahc::{config::Dialer, prelude::*, send, Body, Request, Response};
+use serde::de::DeserializeOwned;
+use std::collections::HashMap;
 use std::io::{Error, ErrorKind, Read};
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// Default time between two inspections in `Docker::wait_healthy`.
+const HEALTH_POLL_INTERVAL: Duration = Duration::from_millis(250);
 
 pub struct Docker {
+    socket: PathBuf,
     dialer: Dialer,
+    api_version: Option<String>,
+    connect_timeout: Option<Duration>,
+    timeout: Option<Duration>,
+    next_timeout: Option<Duration>,
+    retry: Option<RetryPolicy>,
+    health_poll_interval: Duration,
 }
 
 impl Docker {
     pub fn connect() -> std::io::Result<Docker> {
-        let path = String::from("/var/run/docker.sock");
-        let file = Path::new(&path);
+        Docker::connect_to("/var/run/docker.sock")
+    }
+
+    pub fn connect_to(path: &str) -> std::io::Result<Docker> {
+        let file = Path::new(path);
         if !file.exists() {
             return Err(Error::new(
                 ErrorKind::NotFound,
@@ -27,39 +64,230 @@ impl Docker {
             ));
         }
         let dialer = Dialer::unix_socket(path);
-        Ok(Docker { dialer })
+        Ok(Docker {
+            socket: file.to_path_buf(),
+            dialer,
+            api_version: None,
+            connect_timeout: None,
+            timeout: None,
+            next_timeout: None,
+            retry: None,
+            health_poll_interval: HEALTH_POLL_INTERVAL,
+        })
+    }
+
+    /// Pins every request to a version of the Engine API, such as `1.41`,
+    /// instead of the latest one supported by the daemon.
+    pub fn with_api_version(mut self, version: &str) -> Docker {
+        self.api_version = Some(version.trim_start_matches('v').to_string());
+        self
+    }
+
+    /// Sets the time allowed to connect to the daemon socket, for every call.
+    pub fn with_connect_timeout(mut self, timeout: Duration) -> Docker {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Sets the default time allowed for a call to complete. Streaming calls
+    /// are only bounded while waiting for the daemon to start answering.
+    pub fn with_timeout(mut self, timeout: Duration) -> Docker {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Retries failed `GET` calls according to `policy`.
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Docker {
+        self.retry = Some(policy);
+        self
+    }
+
+    /// Sets how often `wait_healthy` inspects the container, 250 ms if not
+    /// given.
+    pub fn with_health_poll_interval(mut self, interval: Duration) -> Docker {
+        self.health_poll_interval = interval;
+        self
     }
 
-    fn request(&self, method: Method, url: &str, body: String) -> std::io::Result<String> {
+    /// Overrides the default timeout for the next call only.
+    ///
+    /// ```no_run
+    /// # use std::time::Duration;
+    /// # let mut docker = docker_sync::Docker::connect().unwrap();
+    /// let containers = docker.timeout(Duration::from_secs(30)).get_containers(true);
+    /// ```
+    pub fn timeout(&mut self, timeout: Duration) -> &mut Docker {
+        self.next_timeout = Some(timeout);
+        self
+    }
+
+    fn url(&self, url: &str) -> String {
+        match &self.api_version {
+            Some(version) => format!("/v{}{}", version, url),
+            None => url.to_string(),
+        }
+    }
+
+    fn send(
+        &mut self,
+        method: Method,
+        url: &str,
+        headers: &[(&str, &str)],
+        body: Body,
+    ) -> std::io::Result<Response<Body>> {
+        let timeout = self.next_timeout.take().or(self.timeout);
+
         #[cfg(unix)]
-        let req = Request::builder()
-            .uri(format!("http://localhost{}", url))
-            .header("Content-Type", "application/json")
-            .header("Accept", "application/json")
+        let mut builder = Request::builder()
+            .uri(format!("http://localhost{}", self.url(url)))
             .method(method)
-            .dial(self.dialer.clone())
-            .body(Body::from(body))
-            .expect("failed to build request");
+            .dial(self.dialer.clone());
+        if let Some(timeout) = self.connect_timeout {
+            builder = builder.connect_timeout(timeout);
+        }
+        if let Some(timeout) = timeout {
+            builder = builder.timeout(timeout);
+        }
+        let mut req = builder.body(body).expect("failed to build request");
+
+        let req_headers = req.headers_mut();
+        req_headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        req_headers.insert(ACCEPT, HeaderValue::from_static("application/json"));
+        for (name, value) in headers {
+            let name = match HeaderName::from_bytes(name.as_bytes()) {
+                Ok(name) => name,
+                Err(e) => return Err(Error::new(ErrorKind::InvalidInput, e.to_string())),
+            };
+            let value = match HeaderValue::from_str(value) {
+                Ok(value) => value,
+                Err(e) => return Err(Error::new(ErrorKind::InvalidInput, e.to_string())),
+            };
+            req_headers.insert(name, value);
+        }
 
         let mut res = send(req)?;
 
         if !res.status().is_success() {
-            let mut reason = String::new();
-            if res.status().is_client_error() {
-                reason.push_str("Client error:");
-            } else if res.status().is_server_error() {
-                reason.push_str("Server error:");
+            let mut body = String::new();
+            let _ = res.body_mut().read_to_string(&mut body);
+            return Err(ApiError::new(res.status(), &body).into());
+        }
+
+        Ok(res)
+    }
+
+    fn request(&mut self, method: Method, url: &str, body: String) -> std::io::Result<String> {
+        let policy = match (&method, &self.retry) {
+            (&Method::GET, Some(policy)) => policy.clone(),
+            _ => return self.request_once(method, url, body),
+        };
+
+        let timeout = self.next_timeout;
+        let mut attempt = 1;
+        loop {
+            self.next_timeout = timeout;
+            let error = match self.request_once(method.clone(), url, body.clone()) {
+                Ok(body) => return Ok(body),
+                Err(e) => e,
+            };
+            if attempt >= policy.max_attempts || !policy.is_retryable(&error) {
+                return Err(error);
             }
-            reason.push_str(res.status().canonical_reason().unwrap());
-            return Err(Error::new(ErrorKind::Other, reason));
+            let delay = policy.delay(attempt);
+            policy.notify(&RetryAttempt {
+                attempt,
+                url,
+                error: &error,
+                delay,
+            });
+            std::thread::sleep(delay);
+            attempt += 1;
         }
+    }
+
+    fn request_once(&mut self, method: Method, url: &str, body: String) -> std::io::Result<String> {
+        let mut res = self.send(method, url, &[], Body::from(body))?;
 
         let body = res.body_mut();
         let mut buf = String::new();
-        if let Err(e) = body.read_to_string(&mut buf) {
-            panic!("{}", e);
+        match body.read_to_string(&mut buf) {
+            Ok(_) => Ok(buf),
+            // isahc reports a body cut short as a bare `Other` error, which
+            // the retry policy could not tell from any other failure.
+            Err(e) if e.kind() == ErrorKind::Other => Err(Error::new(ErrorKind::UnexpectedEof, e)),
+            Err(e) => Err(e),
         }
-        Ok(buf)
+    }
+
+    /// Sends a request whose response is streamed, on a dedicated
+    /// connection. The timeout only bounds the wait for the daemon to start
+    /// answering, so that neither a long transfer nor a slow build is cut.
+    fn open(
+        &mut self,
+        method: &str,
+        url: &str,
+        headers: &[(&str, &str)],
+        body: conn::RequestBody,
+    ) -> std::io::Result<conn::Response> {
+        let timeout = self.next_timeout.take().or(self.timeout);
+        let stream = conn::connect(&self.socket, self.connect_timeout)?;
+        conn::request(stream, method, &self.url(url), headers, body, timeout)
+    }
+
+    /// Opens a streaming endpoint on a dedicated connection, so the stream
+    /// can be cancelled from another thread.
+    fn stream<T: DeserializeOwned>(&mut self, url: &str) -> std::io::Result<JsonStream<T>> {
+        let res = self.open("GET", url, &[], RequestBody::Bytes(&[]))?;
+        JsonStream::new(res.into_body())
+    }
+
+    //
+    // Raw requests
+    //
+
+    /// Sends a request to an endpoint the crate does not model, going through
+    /// the same transport, version prefix and error handling as the typed
+    /// calls. The `Content-Type` defaults to `application/json`.
+    pub fn raw_request(
+        &mut self,
+        method: Method,
+        path: &str,
+        query: &[(&str, &str)],
+        headers: &[(&str, &str)],
+        body: RawBody,
+    ) -> std::io::Result<RawResponse> {
+        let mut url = path.to_string();
+        for (i, (key, value)) in query.iter().enumerate() {
+            url.push(if i == 0 { '?' } else { '&' });
+            url.push_str(&format!("{}={}", encode(key), encode(value)));
+        }
+
+        let res = match body {
+            RawBody::Empty => self.open(method.as_str(), &url, headers, RequestBody::Bytes(&[]))?,
+            RawBody::Bytes(bytes) => {
+                self.open(method.as_str(), &url, headers, RequestBody::Bytes(&bytes))?
+            }
+            RawBody::Reader(mut reader) => self.open(
+                method.as_str(),
+                &url,
+                headers,
+                RequestBody::Reader(&mut reader),
+            )?,
+        };
+        Ok(RawResponse::new(res))
+    }
+
+    pub fn raw_get(&mut self, path: &str, query: &[(&str, &str)]) -> std::io::Result<RawResponse> {
+        self.raw_request(Method::GET, path, query, &[], RawBody::Empty)
+    }
+
+    pub fn raw_post(
+        &mut self,
+        path: &str,
+        query: &[(&str, &str)],
+        body: RawBody,
+    ) -> std::io::Result<RawResponse> {
+        self.raw_request(Method::POST, path, query, &[], body)
     }
 
     //
@@ -67,7 +295,15 @@ impl Docker {
     //
 
     pub fn get_networks(&mut self) -> std::io::Result<Vec<Network>> {
-        let body = self.request(Method::GET, "/networks", "".to_string())?;
+        self.list_networks(&NetworkListOptions::default())
+    }
+
+    pub fn list_networks(&mut self, options: &NetworkListOptions) -> std::io::Result<Vec<Network>> {
+        let body = self.request(
+            Method::GET,
+            &format!("/networks{}", filters_query('?', &options.to_map())),
+            "".to_string(),
+        )?;
 
         match serde_json::from_str(&body) {
             Ok(networks) => Ok(networks),
@@ -78,27 +314,45 @@ impl Docker {
         }
     }
 
-    pub fn create_network(&mut self, network: NetworkCreate) -> std::io::Result<String> {
+    pub fn inspect_network(
+        &mut self,
+        id_or_name: &str,
+        verbose: bool,
+        scope: Option<&str>,
+    ) -> std::io::Result<Network> {
+        let mut url = format!("/networks/{}?verbose={}", encode(id_or_name), verbose);
+        if let Some(scope) = scope {
+            url.push_str(&format!("&scope={}", encode(scope)));
+        }
+
+        let body = self.request(Method::GET, &url, "".to_string())?;
+
+        match serde_json::from_str(&body) {
+            Ok(network) => Ok(network),
+            Err(e) => Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                e.to_string(),
+            )),
+        }
+    }
+
+    pub fn create_network(&mut self, network: NetworkCreate) -> std::io::Result<NetworkCreated> {
         let body = self.request(
             Method::POST,
             "/networks/create",
             serde_json::to_string(&network).unwrap(),
         )?;
 
-        let status: serde_json::Value = match serde_json::from_str(&body) {
-            Ok(status) => status,
-            Err(e) => {
-                return Err(std::io::Error::new(
-                    std::io::ErrorKind::InvalidInput,
-                    e.to_string(),
-                ));
+        match serde_json::from_str::<NetworkCreated>(&body) {
+            Ok(mut created) => {
+                if created.warning.as_deref() == Some("") {
+                    created.warning = None;
+                }
+                Ok(created)
             }
-        };
-        match status.get("Id") {
-            Some(id) => Ok(id.as_str().unwrap().to_string()),
-            _ => Err(std::io::Error::new(
+            Err(e) => Err(std::io::Error::new(
                 std::io::ErrorKind::InvalidInput,
-                status.get("message").unwrap().to_string(),
+                e.to_string(),
             )),
         }
     }
@@ -119,6 +373,138 @@ impl Docker {
         }
     }
 
+    pub fn connect_network(
+        &mut self,
+        id_or_name: &str,
+        container: &str,
+        endpoint: Option<EndpointConfig>,
+    ) -> std::io::Result<()> {
+        let connect = NetworkConnect {
+            Container: container.to_string(),
+            EndpointConfig: endpoint,
+        };
+        self.request(
+            Method::POST,
+            &format!("/networks/{}/connect", encode(id_or_name)),
+            serde_json::to_string(&connect).unwrap(),
+        )?;
+        Ok(())
+    }
+
+    pub fn disconnect_network(
+        &mut self,
+        id_or_name: &str,
+        container: &str,
+        force: bool,
+    ) -> std::io::Result<()> {
+        let disconnect = NetworkDisconnect {
+            Container: container.to_string(),
+            Force: force,
+        };
+        self.request(
+            Method::POST,
+            &format!("/networks/{}/disconnect", encode(id_or_name)),
+            serde_json::to_string(&disconnect).unwrap(),
+        )?;
+        Ok(())
+    }
+
+    pub fn prune_networks(
+        &mut self,
+        filters: &NetworkPruneFilters,
+    ) -> std::io::Result<Vec<String>> {
+        let body = self.request(
+            Method::POST,
+            &format!("/networks/prune{}", filters_query('?', &filters.to_map())),
+            "".to_string(),
+        )?;
+
+        match serde_json::from_str::<NetworkPrune>(&body) {
+            Ok(prune) => Ok(prune.NetworksDeleted.unwrap_or_default()),
+            Err(e) => Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                e.to_string(),
+            )),
+        }
+    }
+
+    //
+    // Volumes
+    //
+
+    pub fn get_volumes(&mut self, options: &VolumeListOptions) -> std::io::Result<VolumeList> {
+        let body = self.request(
+            Method::GET,
+            &format!("/volumes{}", filters_query('?', &options.to_map())),
+            "".to_string(),
+        )?;
+
+        match serde_json::from_str(&body) {
+            Ok(volumes) => Ok(volumes),
+            Err(e) => Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                e.to_string(),
+            )),
+        }
+    }
+
+    pub fn inspect_volume(&mut self, name: &str) -> std::io::Result<Volume> {
+        let body = self.request(
+            Method::GET,
+            &format!("/volumes/{}", encode(name)),
+            "".to_string(),
+        )?;
+
+        match serde_json::from_str(&body) {
+            Ok(volume) => Ok(volume),
+            Err(e) => Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                e.to_string(),
+            )),
+        }
+    }
+
+    pub fn create_volume(&mut self, volume: VolumeCreate) -> std::io::Result<Volume> {
+        let body = self.request(
+            Method::POST,
+            "/volumes/create",
+            serde_json::to_string(&volume).unwrap(),
+        )?;
+
+        match serde_json::from_str(&body) {
+            Ok(volume) => Ok(volume),
+            Err(e) => Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                e.to_string(),
+            )),
+        }
+    }
+
+    pub fn remove_volume(&mut self, name: &str, force: bool) -> std::io::Result<()> {
+        self.request(
+            Method::DELETE,
+            &format!("/volumes/{}?force={}", encode(name), force),
+            "".to_string(),
+        )?;
+        Ok(())
+    }
+
+    pub fn prune_volumes(&mut self, filters: &VolumePruneFilters) -> std::io::Result<VolumePrune> {
+        let body = self.request(
+            Method::POST,
+            &format!("/volumes/prune{}", filters_query('?', &filters.to_map())),
+            "".to_string(),
+        )?;
+
+        match serde_json::from_str(&body) {
+            Ok(prune) => Ok(prune),
+            Err(e) => Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                e.to_string(),
+            )),
+        }
+    }
+
     //
     // Containers
     //
@@ -144,6 +530,145 @@ impl Docker {
         }
     }
 
+    pub fn list_containers(
+        &mut self,
+        options: &ContainerListOptions,
+    ) -> std::io::Result<Vec<Container>> {
+        let mut url = format!("/containers/json?all={}&size={}", options.all, options.size);
+        if let Some(limit) = options.limit {
+            url.push_str(&format!("&limit={}", limit));
+        }
+        url.push_str(&filters_query('&', &options.to_map()));
+
+        let body = self.request(Method::GET, &url, "".to_string())?;
+
+        match serde_json::from_str(&body) {
+            Ok(containers) => Ok(containers),
+            Err(e) => Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                e.to_string(),
+            )),
+        }
+    }
+
+    pub fn create_container(
+        &mut self,
+        name: Option<&str>,
+        container: ContainerCreate,
+    ) -> std::io::Result<ContainerCreated> {
+        let mut url = "/containers/create".to_string();
+        if let Some(name) = name {
+            url.push_str(&format!("?name={}", encode(name)));
+        }
+
+        let body = self.request(
+            Method::POST,
+            &url,
+            serde_json::to_string(&container).unwrap(),
+        )?;
+
+        match serde_json::from_str(&body) {
+            Ok(created) => Ok(created),
+            Err(e) => Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                e.to_string(),
+            )),
+        }
+    }
+
+    pub fn start_container(&mut self, id_or_name: &str) -> std::io::Result<()> {
+        self.request(
+            Method::POST,
+            &format!("/containers/{}/start", encode(id_or_name)),
+            "".to_string(),
+        )?;
+        Ok(())
+    }
+
+    /// Stops a container, killing it if it is still running after `timeout`
+    /// seconds. The daemon's default of 10 seconds is used if not given.
+    pub fn stop_container(
+        &mut self,
+        id_or_name: &str,
+        timeout: Option<u64>,
+    ) -> std::io::Result<()> {
+        let mut url = format!("/containers/{}/stop", encode(id_or_name));
+        if let Some(timeout) = timeout {
+            url.push_str(&format!("?t={}", timeout));
+        }
+        self.request(Method::POST, &url, "".to_string())?;
+        Ok(())
+    }
+
+    pub fn restart_container(
+        &mut self,
+        id_or_name: &str,
+        timeout: Option<u64>,
+    ) -> std::io::Result<()> {
+        let mut url = format!("/containers/{}/restart", encode(id_or_name));
+        if let Some(timeout) = timeout {
+            url.push_str(&format!("?t={}", timeout));
+        }
+        self.request(Method::POST, &url, "".to_string())?;
+        Ok(())
+    }
+
+    /// Sends `signal` (such as `SIGHUP` or `9`) to a container, `SIGKILL` if
+    /// not given.
+    pub fn kill_container(
+        &mut self,
+        id_or_name: &str,
+        signal: Option<&str>,
+    ) -> std::io::Result<()> {
+        let mut url = format!("/containers/{}/kill", encode(id_or_name));
+        if let Some(signal) = signal {
+            url.push_str(&format!("?signal={}", encode(signal)));
+        }
+        self.request(Method::POST, &url, "".to_string())?;
+        Ok(())
+    }
+
+    pub fn pause_container(&mut self, id_or_name: &str) -> std::io::Result<()> {
+        self.request(
+            Method::POST,
+            &format!("/containers/{}/pause", encode(id_or_name)),
+            "".to_string(),
+        )?;
+        Ok(())
+    }
+
+    pub fn unpause_container(&mut self, id_or_name: &str) -> std::io::Result<()> {
+        self.request(
+            Method::POST,
+            &format!("/containers/{}/unpause", encode(id_or_name)),
+            "".to_string(),
+        )?;
+        Ok(())
+    }
+
+    /// Removes a container. `volumes` also removes its anonymous volumes, and
+    /// `link` removes the link of that name instead of the container.
+    pub fn remove_container(
+        &mut self,
+        id_or_name: &str,
+        force: bool,
+        volumes: bool,
+        link: bool,
+    ) -> std::io::Result<()> {
+        self.request(
+            Method::DELETE,
+            &format!(
+                "/containers/{}?force={}&v={}&link={}",
+                encode(id_or_name),
+                force,
+                volumes,
+                link
+            ),
+            "".to_string(),
+        )?;
+        Ok(())
+    }
+
     pub fn get_processes(&mut self, container: &Container) -> std::io::Result<Vec<Process>> {
         let body = self.request(
             Method::GET,
@@ -204,7 +729,12 @@ impl Docker {
     }
 
     pub fn get_stats(&mut self, container: &Container) -> std::io::Result<Stats> {
-        if !container.Status.contains("Up") {
+        let running = match &container.State {
+            Some(state) => state.is_running(),
+            // Old daemons do not list the state, so ask for it.
+            None => self.inspect_container(&container.Id)?.State.Running,
+        };
+        if !running {
             let err = std::io::Error::new(
                 std::io::ErrorKind::InvalidInput,
                 "The container is already stopped.",
@@ -214,7 +744,7 @@ impl Docker {
 
         let body = self.request(
             Method::GET,
-            &format!("/containers/{}/stats", container.Id),
+            &format!("/containers/{}/stats?stream=false", container.Id),
             "".to_string(),
         )?;
 
@@ -227,6 +757,323 @@ impl Docker {
         }
     }
 
+    pub fn stream_stats(&mut self, container: &Container) -> std::io::Result<JsonStream<Stats>> {
+        self.stream(&format!("/containers/{}/stats?stream=true", container.Id))
+    }
+
+    /// Blocks until the container meets `condition`, and returns its exit
+    /// code. The default timeout only bounds the wait for the daemon to
+    /// answer, while one set with `Docker::timeout` also bounds the wait for
+    /// the container.
+    pub fn wait_container(
+        &mut self,
+        id_or_name: &str,
+        condition: WaitCondition,
+    ) -> std::io::Result<WaitResponse> {
+        let wait = self.next_timeout;
+        let res = self.open(
+            "POST",
+            &format!(
+                "/containers/{}/wait?condition={}",
+                encode(id_or_name),
+                condition.as_str()
+            ),
+            &[],
+            RequestBody::Bytes(&[]),
+        )?;
+
+        let mut res = res.into_body();
+        res.stream().set_read_timeout(wait)?;
+        let mut body = String::new();
+        if let Err(e) = res.read_to_string(&mut body) {
+            return match e.kind() {
+                ErrorKind::WouldBlock => Err(Error::new(ErrorKind::TimedOut, "timed out")),
+                _ => Err(e),
+            };
+        }
+        match serde_json::from_str(&body) {
+            Ok(response) => Ok(response),
+            Err(e) => Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                e.to_string(),
+            )),
+        }
+    }
+
+    /// Polls the health of a container until it is healthy. Fails at once if
+    /// the container has no healthcheck or is not running, and with a
+    /// `HealthTimeout` holding the last healthcheck runs after `timeout`.
+    pub fn wait_healthy(&mut self, id_or_name: &str, timeout: Duration) -> std::io::Result<Health> {
+        let start = Instant::now();
+        loop {
+            let state = self.inspect_container(id_or_name)?.State;
+            if !state.Running {
+                return Err(Error::other(format!(
+                    "container {} is not running",
+                    id_or_name
+                )));
+            }
+            let health = match state.Health {
+                Some(health) => health,
+                None => {
+                    return Err(Error::new(
+                        ErrorKind::InvalidInput,
+                        format!("container {} has no healthcheck", id_or_name),
+                    ))
+                }
+            };
+            if health.is_healthy() {
+                return Ok(health);
+            }
+
+            let elapsed = start.elapsed();
+            if elapsed >= timeout {
+                return Err(HealthTimeout { timeout, health }.into());
+            }
+            std::thread::sleep(self.health_poll_interval.min(timeout - elapsed));
+        }
+    }
+
+    /// Attaches to the stdio of a container. The daemon hijacks the
+    /// connection, which is then used for both directions.
+    pub fn attach(
+        &mut self,
+        id_or_name: &str,
+        options: &AttachOptions,
+    ) -> std::io::Result<Attached> {
+        // Output is only multiplexed when the container has no TTY.
+        let tty = self
+            .inspect_container(id_or_name)?
+            .extra_value("Config")
+            .and_then(|config| config.get("Tty"))
+            .and_then(|tty| tty.as_bool());
+
+        let res = self.open(
+            "POST",
+            &format!(
+                "/containers/{}/attach?{}",
+                encode(id_or_name),
+                options.query()
+            ),
+            &[("Connection", "Upgrade"), ("Upgrade", "tcp")],
+            RequestBody::Bytes(&[]),
+        )?;
+        Attached::new(res.into_body(), tty.unwrap_or(false))
+    }
+
+    /// Resizes the TTY of a container, in characters.
+    pub fn resize_container(
+        &mut self,
+        id_or_name: &str,
+        height: u32,
+        width: u32,
+    ) -> std::io::Result<()> {
+        self.request(
+            Method::POST,
+            &format!(
+                "/containers/{}/resize?h={}&w={}",
+                encode(id_or_name),
+                height,
+                width
+            ),
+            "".to_string(),
+        )?;
+        Ok(())
+    }
+
+    /// Changes the resource limits or restart policy of a container while it
+    /// runs, and returns the warnings of the daemon, such as limits the kernel
+    /// does not support.
+    pub fn update_container(
+        &mut self,
+        id_or_name: &str,
+        update: ResourceUpdate,
+    ) -> std::io::Result<ContainerUpdated> {
+        let body = self.request(
+            Method::POST,
+            &format!("/containers/{}/update", encode(id_or_name)),
+            serde_json::to_string(&update).unwrap(),
+        )?;
+
+        match serde_json::from_str(&body) {
+            Ok(updated) => Ok(updated),
+            Err(e) => Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                e.to_string(),
+            )),
+        }
+    }
+
+    /// Returns the resource limits in effect for a container, as reported by
+    /// inspect.
+    pub fn container_resources(&mut self, id_or_name: &str) -> std::io::Result<ResourceUpdate> {
+        match self.inspect_container(id_or_name)?.HostConfig {
+            Some(host_config) => Ok(host_config.resources()),
+            None => Err(Error::new(
+                ErrorKind::InvalidData,
+                "inspect response has no HostConfig",
+            )),
+        }
+    }
+
+    /// Returns the metadata of a path inside a container.
+    pub fn stat_path(&mut self, id_or_name: &str, path: &str) -> std::io::Result<PathStat> {
+        let res = self.send(
+            Method::HEAD,
+            &format!(
+                "/containers/{}/archive?path={}",
+                encode(id_or_name),
+                encode(path)
+            ),
+            &[],
+            Body::empty(),
+        )?;
+
+        match res.headers().get("X-Docker-Container-Path-Stat") {
+            Some(header) => PathStat::from_header(header.to_str().unwrap_or("")),
+            None => Err(Error::new(
+                ErrorKind::InvalidData,
+                "missing X-Docker-Container-Path-Stat header",
+            )),
+        }
+    }
+
+    /// Downloads a file or directory from a container as a tar archive.
+    pub fn download_path(&mut self, id_or_name: &str, path: &str) -> std::io::Result<TarStream> {
+        let res = self.open(
+            "GET",
+            &format!(
+                "/containers/{}/archive?path={}",
+                encode(id_or_name),
+                encode(path)
+            ),
+            &[],
+            RequestBody::Bytes(&[]),
+        )?;
+
+        Ok(TarStream::new(res.into_body()))
+    }
+
+    /// Extracts a tar archive into the directory `path` of a container. With
+    /// `no_overwrite_dir_non_dir`, the upload fails rather than replace a
+    /// directory with a file or the other way around.
+    pub fn upload_path<R>(
+        &mut self,
+        id_or_name: &str,
+        path: &str,
+        mut archive: R,
+        no_overwrite_dir_non_dir: bool,
+    ) -> std::io::Result<()>
+    where
+        R: Read + Send + Sync + 'static,
+    {
+        self.open(
+            "PUT",
+            &format!(
+                "/containers/{}/archive?path={}&noOverwriteDirNonDir={}",
+                encode(id_or_name),
+                encode(path),
+                no_overwrite_dir_non_dir
+            ),
+            &[("Content-Type", "application/x-tar")],
+            RequestBody::Reader(&mut archive),
+        )?;
+        Ok(())
+    }
+
+    /// Exports the filesystem of a container as a tar archive, streamed as
+    /// the daemon sends it.
+    pub fn export_container(&mut self, id_or_name: &str) -> std::io::Result<TarStream> {
+        let res = self.open(
+            "GET",
+            &format!("/containers/{}/export", encode(id_or_name)),
+            &[("Accept", "application/x-tar")],
+            RequestBody::Bytes(&[]),
+        )?;
+
+        Ok(TarStream::new(res.into_body()))
+    }
+
+    //
+    // Exec
+    //
+
+    /// Runs a command in a running container and waits for it to exit,
+    /// returning its output and exit code.
+    pub fn exec(&mut self, id_or_name: &str, options: &ExecOptions) -> std::io::Result<ExecOutput> {
+        let session = self.start_exec(id_or_name, options, false)?;
+        session.finish(self)
+    }
+
+    /// Runs a command in a running container with its stdin attached, and
+    /// returns as soon as it is started.
+    pub fn exec_stream(
+        &mut self,
+        id_or_name: &str,
+        options: &ExecOptions,
+    ) -> std::io::Result<ExecSession> {
+        self.start_exec(id_or_name, options, true)
+    }
+
+    pub fn inspect_exec(&mut self, id: &str) -> std::io::Result<ExecInspect> {
+        let body = self.request(
+            Method::GET,
+            &format!("/exec/{}/json", encode(id)),
+            "".to_string(),
+        )?;
+
+        match serde_json::from_str(&body) {
+            Ok(inspect) => Ok(inspect),
+            Err(e) => Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                e.to_string(),
+            )),
+        }
+    }
+
+    /// Resizes the TTY of an exec started with `tty` set, in characters.
+    pub fn resize_exec(&mut self, id: &str, height: u32, width: u32) -> std::io::Result<()> {
+        self.request(
+            Method::POST,
+            &format!("/exec/{}/resize?h={}&w={}", encode(id), height, width),
+            "".to_string(),
+        )?;
+        Ok(())
+    }
+
+    fn start_exec(
+        &mut self,
+        id_or_name: &str,
+        options: &ExecOptions,
+        attach_stdin: bool,
+    ) -> std::io::Result<ExecSession> {
+        let body = self.request(
+            Method::POST,
+            &format!("/containers/{}/exec", encode(id_or_name)),
+            serde_json::to_string(&options.config(attach_stdin)).unwrap(),
+        )?;
+        let created: ExecCreated = match serde_json::from_str(&body) {
+            Ok(created) => created,
+            Err(e) => {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    e.to_string(),
+                ))
+            }
+        };
+
+        // The daemon hijacks the connection once the exec is started, so it
+        // goes through a dedicated connection rather than isahc.
+        let start = format!("{{\"Detach\":false,\"Tty\":{}}}", options.tty);
+        let res = self.open(
+            "POST",
+            &format!("/exec/{}/start", encode(&created.id)),
+            &[("Connection", "Upgrade"), ("Upgrade", "tcp")],
+            RequestBody::Bytes(start.as_bytes()),
+        )?;
+        ExecSession::new(created.id, res.into_body(), options.tty)
+    }
+
     //
     // Image
     //
@@ -251,6 +1098,99 @@ impl Docker {
         }
     }
 
+    pub fn save_images(&mut self, names: &[&str]) -> std::io::Result<impl Read> {
+        let query: Vec<String> = names
+            .iter()
+            .map(|name| format!("names={}", encode(name)))
+            .collect();
+
+        let res = self.open(
+            "GET",
+            &format!("/images/get?{}", query.join("&")),
+            &[],
+            RequestBody::Bytes(&[]),
+        )?;
+
+        Ok(res.into_body())
+    }
+
+    pub fn load_image<R>(&mut self, mut reader: R, quiet: bool) -> std::io::Result<Vec<ImageLoad>>
+    where
+        R: Read + Send + Sync + 'static,
+    {
+        let q = match quiet {
+            true => "1",
+            false => "0",
+        };
+
+        let res = self.open(
+            "POST",
+            &format!("/images/load?quiet={}", q),
+            &[("Content-Type", "application/x-tar")],
+            RequestBody::Reader(&mut reader),
+        )?;
+
+        let mut messages = Vec::new();
+        for message in serde_json::Deserializer::from_reader(res.into_body()).into_iter() {
+            let message: ImageLoad = match message {
+                Ok(message) => message,
+                Err(e) => {
+                    return Err(std::io::Error::new(
+                        std::io::ErrorKind::InvalidInput,
+                        e.to_string(),
+                    ));
+                }
+            };
+            if let Some(error) = message.error {
+                return Err(Error::other(error));
+            }
+            messages.push(message);
+        }
+
+        Ok(messages)
+    }
+
+    pub fn build_image<R>(
+        &mut self,
+        mut context: R,
+        options: &BuildOptions,
+    ) -> std::io::Result<BuildStream<impl Read>>
+    where
+        R: Read + Send + Sync + 'static,
+    {
+        let res = self.open(
+            "POST",
+            &format!("/build?{}", build::query(options)),
+            &[("Content-Type", "application/x-tar")],
+            RequestBody::Reader(&mut context),
+        )?;
+
+        Ok(BuildStream::new(res.into_body()))
+    }
+
+    pub fn search_images(
+        &mut self,
+        term: &str,
+        limit: Option<u64>,
+        filters: &ImageSearchFilters,
+    ) -> std::io::Result<Vec<ImageSearchResult>> {
+        let mut url = format!("/images/search?term={}", encode(term));
+        if let Some(limit) = limit {
+            url.push_str(&format!("&limit={}", limit));
+        }
+        url.push_str(&filters_query('&', &filters.to_map()));
+
+        let body = self.request(Method::GET, &url, "".to_string())?;
+
+        match serde_json::from_str(&body) {
+            Ok(results) => Ok(results),
+            Err(e) => Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                e.to_string(),
+            )),
+        }
+    }
+
     pub fn get_system_info(&mut self) -> std::io::Result<SystemInfo> {
         let body = self.request(Method::GET, "/info", "".to_string())?;
 
@@ -263,10 +1203,26 @@ impl Docker {
         }
     }
 
+    pub fn disk_usage(&mut self) -> std::io::Result<DiskUsage> {
+        let body = self.request(Method::GET, "/system/df", "".to_string())?;
+
+        match serde_json::from_str(&body) {
+            Ok(usage) => Ok(usage),
+            Err(e) => Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                e.to_string(),
+            )),
+        }
+    }
+
     pub fn get_container_info(&mut self, container: &Container) -> std::io::Result<ContainerInfo> {
+        self.inspect_container(&container.Id)
+    }
+
+    pub fn inspect_container(&mut self, id_or_name: &str) -> std::io::Result<ContainerInfo> {
         let body = self.request(
             Method::GET,
-            &format!("/containers/{}/json", container.Id),
+            &format!("/containers/{}/json", encode(id_or_name)),
             "".to_string(),
         )?;
 
@@ -320,30 +1276,74 @@ impl Docker {
         since: Option<String>,
         until: Option<String>,
     ) -> std::io::Result<Vec<Event>> {
-        let mut url = "/events".to_string();
-        let mut options = "".to_string();
-        if let Some(since_val) = since {
-            options.push_str("since=");
-            options.push_str(&since_val);
-        }
-        if let Some(until_val) = until {
-            if !options.is_empty() {
-                options.push('&');
+        let body = self.request(Method::GET, &events_url(since, until), "".to_string())?;
+
+        let mut events = Vec::new();
+        for event in serde_json::Deserializer::from_str(&body).into_iter() {
+            match event {
+                Ok(event) => events.push(event),
+                Err(e) => {
+                    return Err(std::io::Error::new(
+                        std::io::ErrorKind::InvalidInput,
+                        e.to_string(),
+                    ));
+                }
             }
-            options.push_str("until=");
-            options.push_str(&until_val);
         }
+        Ok(events)
+    }
+
+    /// Follows events as they happen, starting from `since` if given. The
+    /// stream only ends when cancelled or when the daemon goes away.
+    pub fn stream_events(&mut self, since: Option<String>) -> std::io::Result<JsonStream<Event>> {
+        self.stream(&events_url(since, None))
+    }
+}
+
+fn events_url(since: Option<String>, until: Option<String>) -> String {
+    let mut url = "/events".to_string();
+    let mut options = "".to_string();
+    if let Some(since_val) = since {
+        options.push_str("since=");
+        options.push_str(&encode(&since_val));
+    }
+    if let Some(until_val) = until {
         if !options.is_empty() {
-            url.push('?');
-            url.push_str(&options);
+            options.push('&');
         }
-        let body = self.request(Method::GET, &url, "".to_string())?;
-        match serde_json::from_str(&body) {
-            Ok(r_body) => Ok(r_body),
-            Err(e) => Err(std::io::Error::new(
-                std::io::ErrorKind::InvalidInput,
-                e.to_string(),
-            )),
+        options.push_str("until=");
+        options.push_str(&encode(&until_val));
+    }
+    if !options.is_empty() {
+        url.push('?');
+        url.push_str(&options);
+    }
+    url
+}
+
+/// Formats filters as a `filters` query string parameter, preceded by
+/// `separator`, or returns an empty string when there are none.
+fn filters_query(separator: char, filters: &HashMap<String, Vec<String>>) -> String {
+    if filters.is_empty() {
+        return String::new();
+    }
+    format!(
+        "{}filters={}",
+        separator,
+        encode(&serde_json::to_string(filters).unwrap())
+    )
+}
+
+/// Percent-encodes a value so it can be passed as a query string parameter.
+pub(crate) fn encode(value: &str) -> String {
+    let mut encoded = String::new();
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
         }
     }
+    encoded
 }
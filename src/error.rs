@@ -0,0 +1,79 @@
+use http::StatusCode;
+use std::io::ErrorKind;
+
+/// Error returned by the daemon, carried inside the `std::io::Error` of any
+/// failed call. Use `ApiError::from_io` to get it back.
+#[derive(Debug, Clone)]
+pub struct ApiError {
+    pub status: StatusCode,
+    pub message: String,
+}
+
+#[derive(Deserialize)]
+struct ErrorResponse {
+    message: String,
+}
+
+impl ApiError {
+    pub(crate) fn new(status: StatusCode, body: &str) -> Self {
+        let message = match serde_json::from_str::<ErrorResponse>(body) {
+            Ok(response) => response.message,
+            Err(_) => body.trim().to_string(),
+        };
+        ApiError { status, message }
+    }
+
+    pub fn from_io(error: &std::io::Error) -> Option<&ApiError> {
+        error.get_ref()?.downcast_ref::<ApiError>()
+    }
+
+    pub fn is_not_modified(&self) -> bool {
+        self.status == StatusCode::NOT_MODIFIED
+    }
+
+    pub fn is_not_found(&self) -> bool {
+        self.status == StatusCode::NOT_FOUND
+    }
+
+    pub fn is_conflict(&self) -> bool {
+        self.status == StatusCode::CONFLICT
+    }
+
+    fn kind(&self) -> ErrorKind {
+        match self.status {
+            StatusCode::BAD_REQUEST => ErrorKind::InvalidInput,
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => ErrorKind::PermissionDenied,
+            StatusCode::NOT_FOUND => ErrorKind::NotFound,
+            StatusCode::CONFLICT => ErrorKind::AlreadyExists,
+            _ => ErrorKind::Other,
+        }
+    }
+}
+
+impl std::fmt::Display for ApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::result::Result<(), std::fmt::Error> {
+        if self.status.is_client_error() {
+            write!(f, "Client error:")?;
+        } else if self.status.is_server_error() {
+            write!(f, "Server error:")?;
+        }
+        write!(f, "{}", self.status.canonical_reason().unwrap_or("Unknown"))?;
+        if !self.message.is_empty() {
+            write!(f, ": {}", self.message)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ApiError {}
+
+impl From<ApiError> for std::io::Error {
+    fn from(error: ApiError) -> Self {
+        std::io::Error::new(error.kind(), error)
+    }
+}
+
+/// Error for a value rejected before it is sent to the daemon.
+pub(crate) fn invalid(message: String) -> std::io::Error {
+    std::io::Error::new(ErrorKind::InvalidInput, message)
+}
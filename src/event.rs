@@ -1,3 +1,4 @@
+use serde_json::{Map, Value};
 use std::collections::HashMap;
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -5,13 +6,18 @@ use std::collections::HashMap;
 pub struct Event {
     pub Type: String,
     pub Action: String,
-    pub status: String,
-    pub id: String,
+    /// Same as `Action`, only sent for container and image events.
+    pub status: Option<String>,
+    /// Same as `Actor.ID`, only sent for container and image events.
+    pub id: Option<String>,
     pub Actor: Actor,
     pub scope: String,
-    pub time: String,
-    pub timeNano: Option<String>,
+    /// Seconds since the epoch.
+    pub time: i64,
+    pub timeNano: Option<i64>,
     pub Experimental: Option<bool>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -19,4 +25,6 @@ pub struct Event {
 pub struct Actor {
     pub ID: String,
     pub Attributes: HashMap<String, String>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
 }
@@ -0,0 +1,157 @@
+use crate::conn;
+use crate::docker::Docker;
+use crate::stdio::{Demux, StdStream, StdinWriter};
+use serde_json::{Map, Value};
+
+/// What to run with `Docker::exec`. Unset fields fall back to the
+/// container's own configuration.
+#[derive(Debug, Clone, Default)]
+pub struct ExecOptions {
+    pub cmd: Vec<String>,
+    /// Variables given as `KEY=value`.
+    pub env: Vec<String>,
+    pub user: Option<String>,
+    pub working_dir: Option<String>,
+    pub tty: bool,
+    pub privileged: bool,
+}
+
+impl ExecOptions {
+    pub fn new(cmd: &[&str]) -> Self {
+        ExecOptions {
+            cmd: cmd.iter().map(|s| s.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    pub(crate) fn config(&self, attach_stdin: bool) -> ExecConfig {
+        ExecConfig {
+            AttachStdin: attach_stdin,
+            AttachStdout: true,
+            AttachStderr: true,
+            Tty: self.tty,
+            Privileged: self.privileged,
+            Cmd: self.cmd.clone(),
+            Env: match self.env.is_empty() {
+                true => None,
+                false => Some(self.env.clone()),
+            },
+            User: self.user.clone(),
+            WorkingDir: self.working_dir.clone(),
+        }
+    }
+}
+
+/// Body of `POST /containers/{id}/exec`.
+#[derive(Serialize, Debug)]
+#[allow(non_snake_case)]
+pub(crate) struct ExecConfig {
+    pub AttachStdin: bool,
+    pub AttachStdout: bool,
+    pub AttachStderr: bool,
+    pub Tty: bool,
+    pub Privileged: bool,
+    pub Cmd: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Env: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub User: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub WorkingDir: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct ExecCreated {
+    #[serde(rename = "Id")]
+    pub id: String,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[allow(non_snake_case)]
+pub struct ExecInspect {
+    pub ID: String,
+    pub ContainerID: String,
+    pub Running: bool,
+    /// Not set while the process is running.
+    pub ExitCode: Option<i64>,
+    pub Pid: Option<i64>,
+    pub ProcessConfig: Option<ProcessConfig>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[allow(non_snake_case)]
+pub struct ProcessConfig {
+    pub entrypoint: String,
+    pub arguments: Vec<String>,
+    pub privileged: bool,
+    pub tty: bool,
+    pub user: Option<String>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ExecOutput {
+    pub stdout: Vec<u8>,
+    pub stderr: Vec<u8>,
+    pub exit_code: Option<i64>,
+}
+
+impl ExecOutput {
+    pub fn success(&self) -> bool {
+        self.exit_code == Some(0)
+    }
+}
+
+/// A running exec, returned by `Docker::exec_stream`. Iterating over it
+/// yields the output of the process as it comes.
+pub struct ExecSession {
+    id: String,
+    stdin: StdinWriter,
+    output: Demux<conn::Body>,
+}
+
+impl ExecSession {
+    pub(crate) fn new(id: String, body: conn::Body, tty: bool) -> std::io::Result<Self> {
+        Ok(ExecSession {
+            id,
+            stdin: StdinWriter::new(body.stream())?,
+            output: Demux::new(body, tty),
+        })
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Returns a writer to the stdin of the process, which can be moved to
+    /// another thread while the output is read.
+    pub fn stdin(&self) -> std::io::Result<StdinWriter> {
+        self.stdin.try_clone()
+    }
+
+    /// Closes stdin, then waits for the process to exit and returns the rest
+    /// of its output along with its exit code.
+    pub fn finish(self, docker: &mut Docker) -> std::io::Result<ExecOutput> {
+        let _ = self.stdin.close();
+        let (stdout, stderr) = self.output.collect_output()?;
+        let inspect = docker.inspect_exec(&self.id)?;
+        Ok(ExecOutput {
+            stdout,
+            stderr,
+            exit_code: inspect.ExitCode,
+        })
+    }
+}
+
+impl Iterator for ExecSession {
+    type Item = std::io::Result<(StdStream, Vec<u8>)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.output.next()
+    }
+}
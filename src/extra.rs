@@ -0,0 +1,103 @@
+//! Fields sent by the daemon that the crate does not model yet.
+//!
+//! Every response type keeps the keys it does not recognise in a flattened
+//! `extra` map, so they can be reached without waiting for a new release and
+//! are written back out when the value is serialized again.
+
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+
+pub trait Extra {
+    fn extra(&self) -> &Map<String, Value>;
+
+    /// Returns the raw value of an unmodelled field.
+    fn extra_value(&self, key: &str) -> Option<&Value> {
+        self.extra().get(key)
+    }
+
+    /// Returns an unmodelled field as a `T`, or `None` if it is missing or
+    /// does not have the expected type.
+    fn get_extra<T: DeserializeOwned>(&self, key: &str) -> Option<T> {
+        serde_json::from_value(self.extra_value(key)?.clone()).ok()
+    }
+}
+
+macro_rules! impl_extra {
+    ($($t:ty),* $(,)?) => {
+        $(
+            impl Extra for $t {
+                fn extra(&self) -> &Map<String, Value> {
+                    &self.extra
+                }
+            }
+        )*
+    };
+}
+
+impl_extra!(
+    crate::archive::PathStat,
+    crate::build::BuildMessage,
+    crate::build::BuildAux,
+    crate::container::Container,
+    crate::container::Port,
+    crate::container::HostConfig,
+    crate::container::ContainerInfo,
+    crate::container::ContainerCreated,
+    crate::container::ContainerHostConfig,
+    crate::container::ContainerUpdated,
+    crate::container::MountPoint,
+    crate::container::SummaryNetworkSettings,
+    crate::container::State,
+    crate::container::Health,
+    crate::container::HealthLog,
+    crate::event::Event,
+    crate::event::Actor,
+    crate::exec::ExecCreated,
+    crate::exec::ExecInspect,
+    crate::exec::ProcessConfig,
+    crate::filesystem::FilesystemChange,
+    crate::image::Image,
+    crate::image::ImageSearchResult,
+    crate::image::ImageStatus,
+    crate::image::ImageLoad,
+    crate::image::ProgressDetail,
+    crate::image::ErrorDetail,
+    crate::network::Network,
+    crate::network::Ipam,
+    crate::network::IpamConfig,
+    crate::network::NetworkContainer,
+    crate::network::ConfigReference,
+    crate::network::PeerInfo,
+    crate::network::ServiceInfo,
+    crate::network::Task,
+    crate::network::EndpointSettings,
+    crate::network::NetworkCreated,
+    crate::network::NetworkPrune,
+    crate::process::Top,
+    crate::stats::Stats,
+    crate::stats::Network,
+    crate::stats::MemoryStats,
+    crate::stats::MemoryStat,
+    crate::stats::CpuStats,
+    crate::stats::CpuUsage,
+    crate::stats::ThrottlingData,
+    crate::stats::BlkioStats,
+    crate::stats::BlkioStat,
+    crate::system::SystemInfo,
+    crate::system::Runtime,
+    crate::system::SwarmInfo,
+    crate::system::PeerNode,
+    crate::system::RegistryConfig,
+    crate::system::IndexInfo,
+    crate::system::DiskUsage,
+    crate::system::DiskUsageImage,
+    crate::system::DiskUsageContainer,
+    crate::system::BuildCache,
+    crate::version::Version,
+    crate::volume::Volume,
+    crate::volume::UsageData,
+    crate::volume::VolumeList,
+    crate::volume::VolumePrune,
+    crate::wait::WaitResponse,
+    crate::wait::WaitError,
+);
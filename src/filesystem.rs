@@ -1,6 +1,167 @@
-#[derive(Serialize, Deserialize, Debug)]
+use crate::pattern;
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[allow(non_snake_case)]
 pub struct FilesystemChange {
     pub Path: String,
-    pub Kind: u8,
+    pub Kind: ChangeKind,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// Kind of a change in a container filesystem, sent by the daemon as an
+/// integer.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(from = "u8", into = "u8")]
+pub enum ChangeKind {
+    Modified,
+    Added,
+    Deleted,
+    /// A kind this crate does not know about.
+    Unknown(u8),
+}
+
+impl From<u8> for ChangeKind {
+    fn from(kind: u8) -> Self {
+        match kind {
+            0 => ChangeKind::Modified,
+            1 => ChangeKind::Added,
+            2 => ChangeKind::Deleted,
+            other => ChangeKind::Unknown(other),
+        }
+    }
+}
+
+impl From<ChangeKind> for u8 {
+    fn from(kind: ChangeKind) -> Self {
+        match kind {
+            ChangeKind::Modified => 0,
+            ChangeKind::Added => 1,
+            ChangeKind::Deleted => 2,
+            ChangeKind::Unknown(other) => other,
+        }
+    }
+}
+
+/// Shown as in the output of `docker diff`.
+impl std::fmt::Display for ChangeKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::result::Result<(), std::fmt::Error> {
+        match self {
+            ChangeKind::Modified => write!(f, "C"),
+            ChangeKind::Added => write!(f, "A"),
+            ChangeKind::Deleted => write!(f, "D"),
+            ChangeKind::Unknown(kind) => write!(f, "{}", kind),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ChangeCounts {
+    pub modified: usize,
+    pub added: usize,
+    pub deleted: usize,
+}
+
+impl ChangeCounts {
+    pub fn total(&self) -> usize {
+        self.modified + self.added + self.deleted
+    }
+
+    fn count(&mut self, kind: ChangeKind) {
+        match kind {
+            ChangeKind::Modified => self.modified += 1,
+            ChangeKind::Added => self.added += 1,
+            ChangeKind::Deleted => self.deleted += 1,
+            ChangeKind::Unknown(_) => {}
+        }
+    }
+}
+
+/// A path in the tree built by `diff_tree`.
+#[derive(Debug, Clone, Default)]
+pub struct DiffNode {
+    /// Change made to this path itself, if any. Directories often appear
+    /// only because something below them changed.
+    pub kind: Option<ChangeKind>,
+    /// Changes made to this path and everything below it.
+    pub counts: ChangeCounts,
+    pub children: BTreeMap<String, DiffNode>,
+}
+
+impl DiffNode {
+    /// Returns the node of an absolute path, such as `/var/log`.
+    pub fn get(&self, path: &str) -> Option<&DiffNode> {
+        path.split('/')
+            .filter(|s| !s.is_empty())
+            .try_fold(self, |node, name| node.children.get(name))
+    }
+}
+
+/// Arranges the changes of a container by directory, with the number of
+/// changes below each of them.
+pub fn diff_tree(changes: &[FilesystemChange]) -> DiffNode {
+    let mut root = DiffNode::default();
+    for change in changes {
+        let mut node = &mut root;
+        node.counts.count(change.Kind);
+        for name in change.Path.split('/').filter(|s| !s.is_empty()) {
+            node = node.children.entry(name.to_string()).or_default();
+            node.counts.count(change.Kind);
+        }
+        node.kind = Some(change.Kind);
+    }
+    root
+}
+
+/// Keeps the changes matching any of `patterns`, which use the syntax of
+/// `.dockerignore` files. A pattern matching a directory also matches
+/// everything below it.
+pub fn filter_changes<'a>(
+    changes: &'a [FilesystemChange],
+    patterns: &[&str],
+) -> Vec<&'a FilesystemChange> {
+    let patterns: Vec<String> = patterns.iter().map(|p| pattern::clean(p)).collect();
+    changes
+        .iter()
+        .filter(|change| {
+            let path = pattern::clean(&change.Path);
+            patterns
+                .iter()
+                .any(|p| ancestors(&path).any(|prefix| pattern::matches(p, prefix)))
+        })
+        .collect()
+}
+
+/// Returns the changes made outside of `expected`, the paths where the
+/// container is meant to write such as its volumes. The directories leading
+/// to an expected path are not reported as modified, since the daemon lists
+/// them whenever something is created inside.
+pub fn unexpected_writes<'a>(
+    changes: &'a [FilesystemChange],
+    expected: &[&str],
+) -> Vec<&'a FilesystemChange> {
+    let expected: Vec<String> = expected.iter().map(|p| pattern::clean(p)).collect();
+    changes
+        .iter()
+        .filter(|change| {
+            let path = pattern::clean(&change.Path);
+            let inside = expected
+                .iter()
+                .any(|e| ancestors(&path).any(|prefix| prefix == e));
+            let leads_to = change.Kind == ChangeKind::Modified
+                && expected
+                    .iter()
+                    .any(|e| path.is_empty() || e.starts_with(&format!("{}/", path)));
+            !inside && !leads_to
+        })
+        .collect()
+}
+
+/// Iterates over `a`, `a/b` and `a/b/c` for the path `a/b/c`.
+fn ancestors(path: &str) -> impl Iterator<Item = &str> {
+    path.match_indices('/')
+        .map(move |(i, _)| &path[..i])
+        .chain(std::iter::once(path))
 }
@@ -1,3 +1,6 @@
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
 #[derive(Serialize, Deserialize, Debug)]
 #[allow(non_snake_case)]
 pub struct Image {
@@ -7,6 +10,8 @@ pub struct Image {
     pub RepoTags: Vec<String>,
     pub Size: u64,
     pub VirtualSize: u64,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
 }
 
 impl Clone for Image {
@@ -18,21 +23,79 @@ impl Clone for Image {
             RepoTags: self.RepoTags.clone(),
             Size: self.Size,
             VirtualSize: self.VirtualSize,
+            extra: self.extra.clone(),
         }
     }
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ImageSearchResult {
+    pub name: String,
+    pub description: String,
+    pub star_count: u64,
+    pub is_official: bool,
+    pub is_automated: bool,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ImageSearchFilters {
+    pub is_official: Option<bool>,
+    pub is_automated: Option<bool>,
+    pub stars: Option<u64>,
+}
+
+impl ImageSearchFilters {
+    pub(crate) fn to_map(&self) -> HashMap<String, Vec<String>> {
+        let mut filters = HashMap::new();
+        if let Some(is_official) = self.is_official {
+            filters.insert("is-official".to_string(), vec![is_official.to_string()]);
+        }
+        if let Some(is_automated) = self.is_automated {
+            filters.insert("is-automated".to_string(), vec![is_automated.to_string()]);
+        }
+        if let Some(stars) = self.stars {
+            filters.insert("stars".to_string(), vec![stars.to_string()]);
+        }
+        filters
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ImageStatus {
     pub status: Option<String>,
     pub error: Option<String>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
 }
 
-impl Clone for ImageStatus {
-    fn clone(&self) -> Self {
-        ImageStatus {
-            status: self.status.clone(),
-            error: self.status.clone(),
-        }
-    }
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[allow(non_snake_case)]
+pub struct ImageLoad {
+    pub stream: Option<String>,
+    pub status: Option<String>,
+    pub id: Option<String>,
+    pub progress: Option<String>,
+    pub progressDetail: Option<ProgressDetail>,
+    pub error: Option<String>,
+    pub errorDetail: Option<ErrorDetail>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ProgressDetail {
+    pub current: Option<u64>,
+    pub total: Option<u64>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ErrorDetail {
+    pub code: Option<i64>,
+    pub message: Option<String>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
 }
@@ -6,17 +6,34 @@ extern crate serde;
 extern crate serde_json;
 
 // declare modules
+pub mod archive;
+pub mod attach;
+pub mod build;
+mod conn;
 pub mod container;
 mod docker;
+pub mod error;
 pub mod event;
+pub mod exec;
+pub mod extra;
 pub mod filesystem;
 pub mod image;
 pub mod network;
+mod pattern;
 pub mod process;
+pub mod raw;
+pub mod retry;
 pub mod stats;
+pub mod stdio;
+pub mod stream;
 pub mod system;
+#[allow(clippy::assertions_on_constants, clippy::needless_return)]
 mod test;
 pub mod version;
+pub mod volume;
+pub mod wait;
 
 // publicly re-export
 pub use docker::Docker;
+pub use extra::Extra;
+pub use http::{HeaderMap, Method, StatusCode};
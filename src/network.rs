@@ -1,4 +1,7 @@
+use crate::error::invalid;
+use serde_json::{Map, Value};
 use std::collections::HashMap;
+use std::net::IpAddr;
 
 #[derive(Serialize, Deserialize, Debug)]
 #[allow(non_snake_case)]
@@ -13,7 +16,87 @@ pub struct Network {
     pub Attachable: bool,
     pub Ingress: bool,
     pub Options: HashMap<String, String>,
-    pub Labels: Option<HashMap<String, String>>, //IPAM
+    pub Labels: Option<HashMap<String, String>>,
+    pub IPAM: Option<Ipam>,
+    pub Containers: Option<HashMap<String, NetworkContainer>>,
+    pub ConfigFrom: Option<ConfigReference>,
+    pub ConfigOnly: Option<bool>,
+    pub Peers: Option<Vec<PeerInfo>>,
+    pub Services: Option<HashMap<String, ServiceInfo>>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[allow(non_snake_case)]
+pub struct Ipam {
+    pub Driver: String,
+    pub Config: Option<Vec<IpamConfig>>,
+    pub Options: Option<HashMap<String, String>>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[allow(non_snake_case)]
+pub struct IpamConfig {
+    pub Subnet: Option<String>,
+    pub IPRange: Option<String>,
+    pub Gateway: Option<String>,
+    pub AuxiliaryAddresses: Option<HashMap<String, String>>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[allow(non_snake_case)]
+pub struct NetworkContainer {
+    pub Name: String,
+    pub EndpointID: String,
+    pub MacAddress: String,
+    pub IPv4Address: String,
+    pub IPv6Address: String,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[allow(non_snake_case)]
+pub struct ConfigReference {
+    pub Network: String,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[allow(non_snake_case)]
+pub struct PeerInfo {
+    pub Name: String,
+    pub IP: String,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[allow(non_snake_case)]
+pub struct ServiceInfo {
+    pub VIP: String,
+    pub Ports: Option<Vec<String>>,
+    pub LocalLBIndex: u64,
+    pub Tasks: Option<Vec<Task>>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[allow(non_snake_case)]
+pub struct Task {
+    pub Name: String,
+    pub EndpointID: String,
+    pub EndpointIP: String,
+    pub Info: Option<HashMap<String, String>>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
 }
 
 impl Clone for Network {
@@ -30,6 +113,13 @@ impl Clone for Network {
             Ingress: self.Ingress,
             Options: self.Options.clone(),
             Labels: self.Labels.clone(),
+            IPAM: self.IPAM.clone(),
+            Containers: self.Containers.clone(),
+            ConfigFrom: self.ConfigFrom.clone(),
+            ConfigOnly: self.ConfigOnly,
+            Peers: self.Peers.clone(),
+            Services: self.Services.clone(),
+            extra: self.extra.clone(),
         }
     }
 }
@@ -51,7 +141,11 @@ pub struct NetworkCreate {
     pub Ingress: Option<bool>,
     pub EnableIPv6: Option<bool>,
     pub Options: Option<HashMap<String, String>>,
-    pub Labels: Option<HashMap<String, String>>, //IPAM
+    pub Labels: Option<HashMap<String, String>>,
+    pub IPAM: Option<Ipam>,
+    pub ConfigFrom: Option<ConfigReference>,
+    pub ConfigOnly: Option<bool>,
+    pub Scope: Option<String>,
 }
 
 impl Clone for NetworkCreate {
@@ -66,6 +160,32 @@ impl Clone for NetworkCreate {
             EnableIPv6: self.EnableIPv6,
             Options: self.Options.clone(),
             Labels: self.Labels.clone(),
+            IPAM: self.IPAM.clone(),
+            ConfigFrom: self.ConfigFrom.clone(),
+            ConfigOnly: self.ConfigOnly,
+            Scope: self.Scope.clone(),
+        }
+    }
+}
+
+impl NetworkCreate {
+    pub fn builder(name: &str) -> NetworkCreateBuilder {
+        NetworkCreateBuilder {
+            network: NetworkCreate {
+                Name: name.to_string(),
+                CheckDuplicate: None,
+                Driver: None,
+                Internal: None,
+                Attachable: None,
+                Ingress: None,
+                EnableIPv6: None,
+                Options: None,
+                Labels: None,
+                IPAM: None,
+                ConfigFrom: None,
+                ConfigOnly: None,
+                Scope: None,
+            },
         }
     }
 }
@@ -75,3 +195,349 @@ impl std::fmt::Display for NetworkCreate {
         write!(f, "{}", self.Name)
     }
 }
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[allow(non_snake_case)]
+pub struct EndpointConfig {
+    pub Aliases: Option<Vec<String>>,
+    pub Links: Option<Vec<String>>,
+    pub IPAMConfig: Option<EndpointIpamConfig>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[allow(non_snake_case)]
+pub struct EndpointIpamConfig {
+    pub IPv4Address: Option<String>,
+    pub IPv6Address: Option<String>,
+    pub LinkLocalIPs: Option<Vec<String>>,
+}
+
+/// A container's endpoint on a network, as reported by the daemon.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[allow(non_snake_case)]
+pub struct EndpointSettings {
+    pub NetworkID: String,
+    pub EndpointID: String,
+    pub Gateway: String,
+    pub IPAddress: String,
+    pub IPPrefixLen: i64,
+    pub IPv6Gateway: Option<String>,
+    pub GlobalIPv6Address: Option<String>,
+    pub MacAddress: String,
+    pub Aliases: Option<Vec<String>>,
+    pub Links: Option<Vec<String>>,
+    pub IPAMConfig: Option<EndpointIpamConfig>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[allow(non_snake_case)]
+pub struct NetworkConnect {
+    pub Container: String,
+    pub EndpointConfig: Option<EndpointConfig>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[allow(non_snake_case)]
+pub struct NetworkDisconnect {
+    pub Container: String,
+    pub Force: bool,
+}
+
+pub struct NetworkCreateBuilder {
+    network: NetworkCreate,
+}
+
+impl NetworkCreateBuilder {
+    pub fn check_duplicate(mut self, check_duplicate: bool) -> Self {
+        self.network.CheckDuplicate = Some(check_duplicate);
+        self
+    }
+
+    pub fn driver(mut self, driver: &str) -> Self {
+        self.network.Driver = Some(driver.to_string());
+        self
+    }
+
+    pub fn internal(mut self, internal: bool) -> Self {
+        self.network.Internal = Some(internal);
+        self
+    }
+
+    pub fn attachable(mut self, attachable: bool) -> Self {
+        self.network.Attachable = Some(attachable);
+        self
+    }
+
+    pub fn ingress(mut self, ingress: bool) -> Self {
+        self.network.Ingress = Some(ingress);
+        self
+    }
+
+    pub fn enable_ipv6(mut self, enable_ipv6: bool) -> Self {
+        self.network.EnableIPv6 = Some(enable_ipv6);
+        self
+    }
+
+    pub fn option(mut self, key: &str, value: &str) -> Self {
+        self.network
+            .Options
+            .get_or_insert_with(HashMap::new)
+            .insert(key.to_string(), value.to_string());
+        self
+    }
+
+    pub fn label(mut self, key: &str, value: &str) -> Self {
+        self.network
+            .Labels
+            .get_or_insert_with(HashMap::new)
+            .insert(key.to_string(), value.to_string());
+        self
+    }
+
+    pub fn ipam_driver(mut self, driver: &str) -> Self {
+        self.ipam().Driver = driver.to_string();
+        self
+    }
+
+    pub fn ipam_option(mut self, key: &str, value: &str) -> Self {
+        self.ipam()
+            .Options
+            .get_or_insert_with(HashMap::new)
+            .insert(key.to_string(), value.to_string());
+        self
+    }
+
+    pub fn ipam_config(mut self, config: IpamConfig) -> Self {
+        self.ipam().Config.get_or_insert_with(Vec::new).push(config);
+        self
+    }
+
+    pub fn config_from(mut self, network: &str) -> Self {
+        self.network.ConfigFrom = Some(ConfigReference {
+            Network: network.to_string(),
+            extra: Map::new(),
+        });
+        self
+    }
+
+    pub fn config_only(mut self, config_only: bool) -> Self {
+        self.network.ConfigOnly = Some(config_only);
+        self
+    }
+
+    pub fn scope(mut self, scope: &str) -> Self {
+        self.network.Scope = Some(scope.to_string());
+        self
+    }
+
+    /// Checks that every IPAM subnet is a valid CIDR and that its IP range,
+    /// gateway and auxiliary addresses all fall within it.
+    pub fn build(self) -> std::io::Result<NetworkCreate> {
+        if self.network.Name.is_empty() {
+            return Err(invalid("network name must not be empty".to_string()));
+        }
+
+        let configs = self
+            .network
+            .IPAM
+            .iter()
+            .flat_map(|ipam| ipam.Config.iter().flatten());
+        for config in configs {
+            let subnet = match &config.Subnet {
+                Some(subnet) => Cidr::parse(subnet)?,
+                None => {
+                    if config.IPRange.is_some() || config.Gateway.is_some() {
+                        return Err(invalid("IPAM config without a subnet".to_string()));
+                    }
+                    continue;
+                }
+            };
+            if let Some(ip_range) = &config.IPRange {
+                let range = Cidr::parse(ip_range)?;
+                if range.prefix < subnet.prefix || !subnet.contains(&range.addr) {
+                    return Err(invalid(format!(
+                        "IP range {} is not within {}",
+                        ip_range, subnet
+                    )));
+                }
+            }
+            if let Some(gateway) = &config.Gateway {
+                let addr = parse_addr(gateway)?;
+                if !subnet.contains(&addr) {
+                    return Err(invalid(format!(
+                        "gateway {} is not within {}",
+                        gateway, subnet
+                    )));
+                }
+            }
+            for (name, aux) in config.AuxiliaryAddresses.iter().flatten() {
+                let addr = parse_addr(aux)?;
+                if !subnet.contains(&addr) {
+                    return Err(invalid(format!(
+                        "auxiliary address {} ({}) is not within {}",
+                        name, aux, subnet
+                    )));
+                }
+            }
+        }
+
+        Ok(self.network)
+    }
+
+    fn ipam(&mut self) -> &mut Ipam {
+        self.network.IPAM.get_or_insert_with(|| Ipam {
+            Driver: "default".to_string(),
+            Config: None,
+            Options: None,
+            extra: Map::new(),
+        })
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct NetworkCreated {
+    #[serde(rename = "Id")]
+    pub id: String,
+    #[serde(rename = "Warning")]
+    pub warning: Option<String>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NetworkType {
+    Custom,
+    Builtin,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct NetworkListOptions {
+    pub id: Vec<String>,
+    pub name: Vec<String>,
+    pub driver: Vec<String>,
+    /// Labels given as `key` or `key=value`.
+    pub label: Vec<String>,
+    pub scope: Option<String>,
+    pub network_type: Option<NetworkType>,
+    pub dangling: Option<bool>,
+}
+
+impl NetworkListOptions {
+    pub(crate) fn to_map(&self) -> HashMap<String, Vec<String>> {
+        let mut filters = HashMap::new();
+        if !self.id.is_empty() {
+            filters.insert("id".to_string(), self.id.clone());
+        }
+        if !self.name.is_empty() {
+            filters.insert("name".to_string(), self.name.clone());
+        }
+        if !self.driver.is_empty() {
+            filters.insert("driver".to_string(), self.driver.clone());
+        }
+        if !self.label.is_empty() {
+            filters.insert("label".to_string(), self.label.clone());
+        }
+        if let Some(scope) = &self.scope {
+            filters.insert("scope".to_string(), vec![scope.clone()]);
+        }
+        if let Some(network_type) = self.network_type {
+            let network_type = match network_type {
+                NetworkType::Custom => "custom",
+                NetworkType::Builtin => "builtin",
+            };
+            filters.insert("type".to_string(), vec![network_type.to_string()]);
+        }
+        if let Some(dangling) = self.dangling {
+            filters.insert("dangling".to_string(), vec![dangling.to_string()]);
+        }
+        filters
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct NetworkPruneFilters {
+    /// Only prune networks created before this timestamp or duration, such as `24h`.
+    pub until: Option<String>,
+    /// Only prune networks with these labels, given as `key` or `key=value`.
+    pub label: Vec<String>,
+    /// Only prune networks without these labels.
+    pub label_not: Vec<String>,
+}
+
+impl NetworkPruneFilters {
+    pub(crate) fn to_map(&self) -> HashMap<String, Vec<String>> {
+        let mut filters = HashMap::new();
+        if let Some(until) = &self.until {
+            filters.insert("until".to_string(), vec![until.clone()]);
+        }
+        if !self.label.is_empty() {
+            filters.insert("label".to_string(), self.label.clone());
+        }
+        if !self.label_not.is_empty() {
+            filters.insert("label!".to_string(), self.label_not.clone());
+        }
+        filters
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[allow(non_snake_case)]
+pub struct NetworkPrune {
+    pub NetworksDeleted: Option<Vec<String>>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// An IPv4 or IPv6 subnet in CIDR notation, such as `172.20.0.0/16`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Cidr {
+    addr: IpAddr,
+    prefix: u8,
+}
+
+impl Cidr {
+    pub(crate) fn parse(cidr: &str) -> std::io::Result<Cidr> {
+        let (addr, prefix) = match cidr.split_once('/') {
+            Some(parts) => parts,
+            None => return Err(invalid(format!("{} is not in CIDR notation", cidr))),
+        };
+        let addr = parse_addr(addr)?;
+        let max = match addr {
+            IpAddr::V4(_) => 32,
+            IpAddr::V6(_) => 128,
+        };
+        match prefix.parse::<u8>() {
+            Ok(prefix) if prefix <= max => Ok(Cidr { addr, prefix }),
+            _ => Err(invalid(format!("{} has an invalid prefix length", cidr))),
+        }
+    }
+
+    pub(crate) fn contains(&self, addr: &IpAddr) -> bool {
+        match (self.addr, addr) {
+            (IpAddr::V4(net), IpAddr::V4(addr)) => {
+                let mask = u32::MAX.checked_shl(32 - self.prefix as u32).unwrap_or(0);
+                u32::from(net) & mask == u32::from(*addr) & mask
+            }
+            (IpAddr::V6(net), IpAddr::V6(addr)) => {
+                let mask = u128::MAX.checked_shl(128 - self.prefix as u32).unwrap_or(0);
+                u128::from(net) & mask == u128::from(*addr) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+impl std::fmt::Display for Cidr {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::result::Result<(), std::fmt::Error> {
+        write!(f, "{}/{}", self.addr, self.prefix)
+    }
+}
+
+fn parse_addr(addr: &str) -> std::io::Result<IpAddr> {
+    match addr.parse() {
+        Ok(addr) => Ok(addr),
+        Err(_) => Err(invalid(format!("{} is not a valid IP address", addr))),
+    }
+}
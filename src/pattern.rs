@@ -0,0 +1,104 @@
+//! Path patterns as understood by the Docker daemon and CLI.
+//!
+//! Patterns use the shell-style syntax of Go's `filepath.Match`: `*` and `?`
+//! never cross a `/`, `[...]` is a character class (`[^...]` or `[!...]`
+//! negates it) and `\` escapes the next character. As in `.dockerignore`
+//! files, a `**` path segment matches any number of directories.
+
+/// Returns whether `path` (a `/`-separated relative path) matches `pattern`.
+pub(crate) fn matches(pattern: &str, path: &str) -> bool {
+    let pattern: Vec<&str> = pattern.split('/').filter(|s| !s.is_empty()).collect();
+    let path: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+    match_segments(&pattern, &path)
+}
+
+/// Normalizes a pattern the way `filepath.Clean` would, dropping any leading
+/// `/` or `./` so patterns are relative to the root they are matched against.
+pub(crate) fn clean(pattern: &str) -> String {
+    let mut segments: Vec<&str> = Vec::new();
+    for segment in pattern.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                segments.pop();
+            }
+            _ => segments.push(segment),
+        }
+    }
+    segments.join("/")
+}
+
+fn match_segments(pattern: &[&str], path: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((&"**", rest)) => (0..=path.len()).any(|i| match_segments(rest, &path[i..])),
+        Some((segment, rest)) => match path.split_first() {
+            Some((name, path)) => {
+                match_segment(segment.as_bytes(), name.as_bytes()) && match_segments(rest, path)
+            }
+            None => false,
+        },
+    }
+}
+
+fn match_segment(pattern: &[u8], name: &[u8]) -> bool {
+    match pattern.split_first() {
+        None => name.is_empty(),
+        Some((b'*', rest)) => (0..=name.len()).any(|i| match_segment(rest, &name[i..])),
+        Some((b'?', rest)) => !name.is_empty() && match_segment(rest, &name[1..]),
+        Some((b'[', rest)) => match (name.split_first(), match_class(rest)) {
+            (Some((c, name)), Some((class, negated, rest))) => {
+                class_contains(class, *c) != negated && match_segment(rest, name)
+            }
+            _ => false,
+        },
+        Some((b'\\', rest)) if !rest.is_empty() => {
+            name.first() == rest.first() && match_segment(&rest[1..], &name[1..])
+        }
+        Some((c, rest)) => name.first() == Some(c) && match_segment(rest, &name[1..]),
+    }
+}
+
+/// Splits a character class (without its opening `[`) into its body, whether
+/// it is negated and the remaining pattern.
+fn match_class(pattern: &[u8]) -> Option<(&[u8], bool, &[u8])> {
+    let (negated, pattern) = match pattern.first() {
+        Some(b'^') | Some(b'!') => (true, &pattern[1..]),
+        _ => (false, pattern),
+    };
+    let mut i = 0;
+    while i < pattern.len() {
+        match pattern[i] {
+            b'\\' => i += 2,
+            b']' if i > 0 => return Some((&pattern[..i], negated, &pattern[i + 1..])),
+            _ => i += 1,
+        }
+    }
+    None
+}
+
+fn class_contains(class: &[u8], c: u8) -> bool {
+    let mut i = 0;
+    while i < class.len() {
+        let mut low = class[i];
+        if low == b'\\' && i + 1 < class.len() {
+            i += 1;
+            low = class[i];
+        }
+        if i + 2 < class.len() && class[i + 1] == b'-' {
+            let mut high = class[i + 2];
+            i += 2;
+            if high == b'\\' && i + 1 < class.len() {
+                i += 1;
+                high = class[i];
+            }
+            if low <= c && c <= high {
+                return true;
+            }
+        } else if low == c {
+            return true;
+        }
+        i += 1;
+    }
+    false
+}
@@ -1,3 +1,4 @@
+use serde_json::{Map, Value};
 use std::fmt::Error;
 use std::fmt::{Display, Formatter};
 
@@ -20,8 +21,11 @@ pub struct Process {
 pub struct Top {
     pub Titles: Vec<String>,
     pub Processes: Vec<Vec<String>>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
 }
 
+#[allow(clippy::explicit_auto_deref)]
 impl Display for Process {
     fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
         let mut s = String::new();
@@ -0,0 +1,85 @@
+use crate::conn;
+use http::header::{HeaderName, HeaderValue};
+use http::{HeaderMap, StatusCode};
+use std::io::Read;
+
+pub enum RawBody {
+    Empty,
+    Bytes(Vec<u8>),
+    Reader(Box<dyn Read + Send + Sync>),
+}
+
+impl RawBody {
+    pub fn from_reader<R>(reader: R) -> RawBody
+    where
+        R: Read + Send + Sync + 'static,
+    {
+        RawBody::Reader(Box::new(reader))
+    }
+}
+
+impl From<Vec<u8>> for RawBody {
+    fn from(bytes: Vec<u8>) -> Self {
+        RawBody::Bytes(bytes)
+    }
+}
+
+impl From<String> for RawBody {
+    fn from(body: String) -> Self {
+        RawBody::Bytes(body.into_bytes())
+    }
+}
+
+impl From<&str> for RawBody {
+    fn from(body: &str) -> Self {
+        RawBody::Bytes(body.as_bytes().to_vec())
+    }
+}
+
+/// Response to a raw request. The body is read from the daemon as it is
+/// consumed, through the `Read` implementation.
+pub struct RawResponse {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    body: conn::Body,
+}
+
+impl RawResponse {
+    pub(crate) fn new(response: conn::Response) -> Self {
+        let mut headers = HeaderMap::new();
+        for (name, value) in &response.headers {
+            if let (Ok(name), Ok(value)) = (
+                HeaderName::from_bytes(name.as_bytes()),
+                HeaderValue::from_str(value),
+            ) {
+                headers.append(name, value);
+            }
+        }
+        RawResponse {
+            status: response.status,
+            headers,
+            body: response.into_body(),
+        }
+    }
+
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(name)?.to_str().ok()
+    }
+
+    /// Reads the rest of the body and parses it as JSON.
+    pub fn json<T: serde::de::DeserializeOwned>(self) -> std::io::Result<T> {
+        match serde_json::from_reader(self) {
+            Ok(value) => Ok(value),
+            Err(e) => Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                e.to_string(),
+            )),
+        }
+    }
+}
+
+impl Read for RawResponse {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.body.read(buf)
+    }
+}
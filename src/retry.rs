@@ -0,0 +1,112 @@
+use crate::error::ApiError;
+use http::StatusCode;
+use std::io::ErrorKind;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Passed to the retry hook each time a failed call is about to be retried.
+pub struct RetryAttempt<'a> {
+    /// Number of the attempt that failed, starting at 1.
+    pub attempt: u32,
+    pub url: &'a str,
+    pub error: &'a std::io::Error,
+    /// Time waited before the next attempt.
+    pub delay: Duration,
+}
+
+pub type RetryHook = Arc<dyn Fn(&RetryAttempt) + Send + Sync>;
+
+/// How failed reads are retried. Only `GET` calls are ever retried, since
+/// they are the only ones that are safe to send twice.
+#[derive(Clone)]
+pub struct RetryPolicy {
+    /// Total number of attempts, including the first one.
+    pub max_attempts: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    pub multiplier: f64,
+    /// Waits a random duration between half and all of the backoff, so that
+    /// clients do not all retry at the same moment.
+    pub jitter: bool,
+    pub retry_statuses: Vec<StatusCode>,
+    /// Errors that did not come from the daemon, such as a refused connection
+    /// or a truncated body, which are retried.
+    pub retry_errors: Vec<ErrorKind>,
+    /// Called before each retry, see `on_retry`.
+    pub hook: Option<RetryHook>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(5),
+            multiplier: 2.0,
+            jitter: true,
+            retry_statuses: vec![
+                StatusCode::INTERNAL_SERVER_ERROR,
+                StatusCode::BAD_GATEWAY,
+                StatusCode::SERVICE_UNAVAILABLE,
+                StatusCode::GATEWAY_TIMEOUT,
+            ],
+            retry_errors: vec![
+                ErrorKind::NotFound,
+                ErrorKind::ConnectionRefused,
+                ErrorKind::ConnectionReset,
+                ErrorKind::ConnectionAborted,
+                ErrorKind::BrokenPipe,
+                ErrorKind::UnexpectedEof,
+            ],
+            hook: None,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Calls `hook` before each retry, for instance to count or log them.
+    pub fn on_retry<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&RetryAttempt) + Send + Sync + 'static,
+    {
+        self.hook = Some(Arc::new(hook));
+        self
+    }
+
+    pub(crate) fn is_retryable(&self, error: &std::io::Error) -> bool {
+        match ApiError::from_io(error) {
+            Some(api) => self.retry_statuses.contains(&api.status),
+            None => self.retry_errors.contains(&error.kind()),
+        }
+    }
+
+    /// Backoff to wait after the given failed attempt.
+    pub(crate) fn delay(&self, attempt: u32) -> Duration {
+        let backoff = self.initial_backoff.as_secs_f64() * self.multiplier.powi(attempt as i32 - 1);
+        let backoff = backoff.min(self.max_backoff.as_secs_f64());
+        if !self.jitter {
+            return Duration::from_secs_f64(backoff);
+        }
+        Duration::from_secs_f64(backoff * (0.5 + random() / 2.0))
+    }
+
+    pub(crate) fn notify(&self, attempt: &RetryAttempt) {
+        if let Some(hook) = &self.hook {
+            hook(attempt);
+        }
+    }
+}
+
+/// A number in `[0, 1)` to spread out retry delays, scrambled from the clock
+/// with xorshift. Only needs to differ between clients retrying at once.
+fn random() -> f64 {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.subsec_nanos())
+        .unwrap_or(0);
+    let mut x = nanos as u64 ^ 0x9E37_79B9_7F4A_7C15;
+    x ^= x << 13;
+    x ^= x >> 7;
+    x ^= x << 17;
+    (x >> 11) as f64 / (1u64 << 53) as f64
+}
@@ -1,3 +1,5 @@
+use serde_json::{Map, Value};
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct Stats {
     pub read: String,
@@ -5,6 +7,8 @@ pub struct Stats {
     pub memory_stats: MemoryStats,
     pub cpu_stats: CpuStats,
     pub blkio_stats: BlkioStats,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -17,6 +21,8 @@ pub struct Network {
     pub rx_packets: u64,
     pub tx_errors: u64,
     pub tx_bytes: u64,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -26,6 +32,8 @@ pub struct MemoryStats {
     pub failcnt: u64,
     pub limit: u64,
     pub stats: MemoryStat,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -62,6 +70,8 @@ pub struct MemoryStat {
     pub total_pgpgin: u64,
     pub swap: u64,
     pub total_swap: u64,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -69,6 +79,8 @@ pub struct CpuStats {
     pub cpu_usage: CpuUsage,
     pub system_cpu_usage: u64,
     pub throttling_data: ThrottlingData,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -77,6 +89,8 @@ pub struct CpuUsage {
     pub usage_in_usermode: u64,
     pub total_usage: u64,
     pub usage_in_kernelmode: u64,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -84,6 +98,8 @@ pub struct ThrottlingData {
     pub periods: u64,
     pub throttled_periods: u64,
     pub throttled_time: u64,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -96,6 +112,8 @@ pub struct BlkioStats {
     pub io_merged_recursive: Vec<BlkioStat>,
     pub io_time_recursive: Vec<BlkioStat>,
     pub sectors_recursive: Vec<BlkioStat>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -104,6 +122,8 @@ pub struct BlkioStat {
     pub minor: u64,
     pub op: String,
     pub value: u64,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
 }
 
 impl Clone for Stats {
@@ -114,6 +134,7 @@ impl Clone for Stats {
             memory_stats: self.memory_stats.clone(),
             cpu_stats: self.cpu_stats.clone(),
             blkio_stats: self.blkio_stats.clone(),
+            extra: self.extra.clone(),
         }
     }
 }
@@ -129,6 +150,7 @@ impl Clone for Network {
             rx_packets: self.rx_packets,
             tx_errors: self.tx_errors,
             tx_bytes: self.tx_bytes,
+            extra: self.extra.clone(),
         }
     }
 }
@@ -141,6 +163,7 @@ impl Clone for MemoryStats {
             failcnt: self.failcnt,
             limit: self.limit,
             stats: self.stats.clone(),
+            extra: self.extra.clone(),
         }
     }
 }
@@ -180,6 +203,7 @@ impl Clone for MemoryStat {
             total_pgpgin: self.total_pgpgin,
             swap: self.swap,
             total_swap: self.total_swap,
+            extra: self.extra.clone(),
         }
     }
 }
@@ -190,6 +214,7 @@ impl Clone for CpuStats {
             cpu_usage: self.cpu_usage.clone(),
             system_cpu_usage: self.system_cpu_usage,
             throttling_data: self.throttling_data.clone(),
+            extra: self.extra.clone(),
         }
     }
 }
@@ -201,6 +226,7 @@ impl Clone for CpuUsage {
             usage_in_usermode: self.usage_in_usermode,
             total_usage: self.total_usage,
             usage_in_kernelmode: self.usage_in_kernelmode,
+            extra: self.extra.clone(),
         }
     }
 }
@@ -211,6 +237,7 @@ impl Clone for ThrottlingData {
             periods: self.periods,
             throttled_periods: self.throttled_periods,
             throttled_time: self.throttled_time,
+            extra: self.extra.clone(),
         }
     }
 }
@@ -226,6 +253,7 @@ impl Clone for BlkioStats {
             io_merged_recursive: self.io_merged_recursive.clone(),
             io_time_recursive: self.io_time_recursive.clone(),
             sectors_recursive: self.sectors_recursive.clone(),
+            extra: self.extra.clone(),
         }
     }
 }
@@ -237,6 +265,7 @@ impl Clone for BlkioStat {
             minor: self.minor,
             op: self.op.clone(),
             value: self.value,
+            extra: self.extra.clone(),
         }
     }
 }
@@ -0,0 +1,118 @@
+//! Output of exec and attach, which the daemon sends over a hijacked
+//! connection.
+
+use std::io::{ErrorKind, Read, Write};
+use std::net::Shutdown;
+use std::os::unix::net::UnixStream;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StdStream {
+    Stdin,
+    Stdout,
+    Stderr,
+}
+
+/// Splits the output of a process into its stdout and stderr chunks.
+///
+/// Without a TTY the daemon multiplexes both streams, prefixing each frame
+/// with an 8 byte header holding the stream and the frame size. With a TTY
+/// the output is raw, and is all reported as stdout.
+pub struct Demux<R> {
+    reader: R,
+    tty: bool,
+}
+
+impl<R: Read> Demux<R> {
+    pub(crate) fn new(reader: R, tty: bool) -> Self {
+        Demux { reader, tty }
+    }
+
+    /// Reads the remaining output, returning stdout and stderr.
+    pub fn collect_output(self) -> std::io::Result<(Vec<u8>, Vec<u8>)> {
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+        for frame in self {
+            match frame? {
+                (StdStream::Stderr, data) => stderr.extend(data),
+                (_, data) => stdout.extend(data),
+            }
+        }
+        Ok((stdout, stderr))
+    }
+
+    fn read_frame(&mut self) -> std::io::Result<Option<(StdStream, Vec<u8>)>> {
+        if self.tty {
+            let mut buf = vec![0; 8192];
+            let n = self.reader.read(&mut buf)?;
+            if n == 0 {
+                return Ok(None);
+            }
+            buf.truncate(n);
+            return Ok(Some((StdStream::Stdout, buf)));
+        }
+
+        let mut header = [0; 8];
+        match self.reader.read_exact(&mut header) {
+            Ok(()) => {}
+            Err(e) if e.kind() == ErrorKind::UnexpectedEof => return Ok(None),
+            Err(e) => return Err(e),
+        }
+        let stream = match header[0] {
+            0 => StdStream::Stdin,
+            1 => StdStream::Stdout,
+            2 => StdStream::Stderr,
+            other => {
+                return Err(std::io::Error::new(
+                    ErrorKind::InvalidData,
+                    format!("unknown stream {} in frame header", other),
+                ))
+            }
+        };
+        let size = u32::from_be_bytes([header[4], header[5], header[6], header[7]]) as usize;
+        let mut data = vec![0; size];
+        self.reader.read_exact(&mut data)?;
+        Ok(Some((stream, data)))
+    }
+}
+
+impl<R: Read> Iterator for Demux<R> {
+    type Item = std::io::Result<(StdStream, Vec<u8>)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read_frame().transpose()
+    }
+}
+
+/// Writes to the stdin of a process. Call `close` once done, so the process
+/// sees the end of its input.
+pub struct StdinWriter {
+    stream: UnixStream,
+}
+
+impl StdinWriter {
+    pub(crate) fn new(stream: &UnixStream) -> std::io::Result<Self> {
+        Ok(StdinWriter {
+            stream: stream.try_clone()?,
+        })
+    }
+
+    pub fn try_clone(&self) -> std::io::Result<Self> {
+        Ok(StdinWriter {
+            stream: self.stream.try_clone()?,
+        })
+    }
+
+    pub fn close(&self) -> std::io::Result<()> {
+        self.stream.shutdown(Shutdown::Write)
+    }
+}
+
+impl Write for StdinWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.stream.write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.stream.flush()
+    }
+}
@@ -0,0 +1,69 @@
+use crate::conn;
+use serde::de::DeserializeOwned;
+use std::io::{Error, ErrorKind};
+use std::net::Shutdown;
+use std::os::unix::net::UnixStream;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// Stops a stream from another thread. The stream then ends as if the daemon
+/// had closed it, even if it is blocked waiting for data.
+#[derive(Clone)]
+pub struct CancelHandle {
+    stream: Arc<UnixStream>,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancelHandle {
+    pub(crate) fn new(stream: &UnixStream) -> std::io::Result<Self> {
+        Ok(CancelHandle {
+            stream: Arc::new(stream.try_clone()?),
+            cancelled: Arc::new(AtomicBool::new(false)),
+        })
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+        let _ = self.stream.shutdown(Shutdown::Both);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+}
+
+/// Iterator over the JSON messages of a streaming endpoint, such as stats or
+/// events.
+pub struct JsonStream<T> {
+    messages: serde_json::StreamDeserializer<'static, serde_json::de::IoRead<conn::Body>, T>,
+    handle: CancelHandle,
+}
+
+impl<T: DeserializeOwned> JsonStream<T> {
+    pub(crate) fn new(body: conn::Body) -> std::io::Result<Self> {
+        let handle = CancelHandle::new(body.stream())?;
+        Ok(JsonStream {
+            messages: serde_json::Deserializer::from_reader(body).into_iter(),
+            handle,
+        })
+    }
+
+    pub fn cancel_handle(&self) -> CancelHandle {
+        self.handle.clone()
+    }
+}
+
+impl<T: DeserializeOwned> Iterator for JsonStream<T> {
+    type Item = std::io::Result<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.handle.is_cancelled() {
+            return None;
+        }
+        match self.messages.next()? {
+            Ok(message) => Some(Ok(message)),
+            Err(_) if self.handle.is_cancelled() => None,
+            Err(e) => Some(Err(Error::new(ErrorKind::InvalidInput, e.to_string()))),
+        }
+    }
+}
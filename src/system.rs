@@ -1,35 +1,119 @@
+use crate::container::ContainerState;
+use crate::volume::Volume;
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
 #[derive(Serialize, Deserialize, Debug)]
 #[allow(non_snake_case)]
 pub struct SystemInfo {
+    pub ID: String,
     pub Containers: u64,
+    pub ContainersRunning: Option<u64>,
+    pub ContainersPaused: Option<u64>,
+    pub ContainersStopped: Option<u64>,
     pub Images: u64,
     pub Driver: String,
     pub DriverStatus: Vec<(String, String)>,
-    pub ExecutionDriver: String,
+    pub ExecutionDriver: Option<String>, // removed in 1.11
     pub KernelVersion: String,
     pub NCPU: u64,
     pub MemTotal: u64,
     pub Name: String,
-    pub ID: String,
-    pub Debug: u64, // bool
+    #[serde(deserialize_with = "bool_or_int")]
+    pub Debug: bool,
     pub NFd: u64,
     pub NGoroutines: u64,
     pub NEventsListener: u64,
-    pub InitPath: String,
-    pub InitSha1: String,
+    pub InitPath: Option<String>, // removed in 1.11
+    pub InitSha1: Option<String>, // removed in 1.11
     pub IndexServerAddress: String,
-    pub MemoryLimit: u64,    // bool
-    pub SwapLimit: u64,      // bool
-    pub IPv4Forwarding: u64, // bool
+    #[serde(deserialize_with = "bool_or_int")]
+    pub MemoryLimit: bool,
+    #[serde(deserialize_with = "bool_or_int")]
+    pub SwapLimit: bool,
+    #[serde(deserialize_with = "bool_or_int")]
+    pub IPv4Forwarding: bool,
     pub Labels: Option<Vec<String>>,
     pub DockerRootDir: String,
     pub OperatingSystem: String,
+    pub OSType: Option<String>,
+    pub Architecture: Option<String>,
+    pub ServerVersion: Option<String>,
+    pub CgroupDriver: Option<String>,
+    pub CgroupVersion: Option<String>,
+    pub Runtimes: Option<HashMap<String, Runtime>>,
+    pub DefaultRuntime: Option<String>,
+    pub SecurityOptions: Option<Vec<String>>,
+    pub Swarm: Option<SwarmInfo>,
+    pub RegistryConfig: Option<RegistryConfig>,
+    pub Warnings: Option<Vec<String>>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[allow(non_snake_case)]
+pub struct Runtime {
+    pub path: Option<String>,
+    pub runtimeArgs: Option<Vec<String>>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[allow(non_snake_case)]
+pub struct SwarmInfo {
+    pub NodeID: String,
+    pub NodeAddr: String,
+    pub LocalNodeState: String,
+    pub ControlAvailable: bool,
+    pub Error: String,
+    pub RemoteManagers: Option<Vec<PeerNode>>,
+    pub Nodes: Option<u64>,
+    pub Managers: Option<u64>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[allow(non_snake_case)]
+pub struct PeerNode {
+    pub NodeID: String,
+    pub Addr: String,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[allow(non_snake_case)]
+pub struct RegistryConfig {
+    pub InsecureRegistryCIDRs: Option<Vec<String>>,
+    pub IndexConfigs: Option<HashMap<String, IndexInfo>>,
+    pub Mirrors: Option<Vec<String>>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[allow(non_snake_case)]
+pub struct IndexInfo {
+    pub Name: String,
+    pub Mirrors: Option<Vec<String>>,
+    pub Secure: bool,
+    pub Official: bool,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
 }
 
 impl Clone for SystemInfo {
     fn clone(&self) -> Self {
         SystemInfo {
+            ID: self.ID.clone(),
             Containers: self.Containers,
+            ContainersRunning: self.ContainersRunning,
+            ContainersPaused: self.ContainersPaused,
+            ContainersStopped: self.ContainersStopped,
             Images: self.Images,
             Driver: self.Driver.clone(),
             DriverStatus: self.DriverStatus.clone(),
@@ -38,7 +122,6 @@ impl Clone for SystemInfo {
             NCPU: self.NCPU,
             MemTotal: self.MemTotal,
             Name: self.Name.clone(),
-            ID: self.ID.clone(),
             Debug: self.Debug,
             NFd: self.NFd,
             NGoroutines: self.NGoroutines,
@@ -52,6 +135,181 @@ impl Clone for SystemInfo {
             Labels: self.Labels.clone(),
             DockerRootDir: self.DockerRootDir.clone(),
             OperatingSystem: self.OperatingSystem.clone(),
+            OSType: self.OSType.clone(),
+            Architecture: self.Architecture.clone(),
+            ServerVersion: self.ServerVersion.clone(),
+            CgroupDriver: self.CgroupDriver.clone(),
+            CgroupVersion: self.CgroupVersion.clone(),
+            Runtimes: self.Runtimes.clone(),
+            DefaultRuntime: self.DefaultRuntime.clone(),
+            SecurityOptions: self.SecurityOptions.clone(),
+            Swarm: self.Swarm.clone(),
+            RegistryConfig: self.RegistryConfig.clone(),
+            Warnings: self.Warnings.clone(),
+            extra: self.extra.clone(),
         }
     }
 }
+
+/// Old daemons send some flags as `0`/`1` integers, newer ones as booleans.
+fn bool_or_int<'de, D>(deserializer: D) -> Result<bool, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum BoolOrInt {
+        Bool(bool),
+        Int(u64),
+    }
+
+    match BoolOrInt::deserialize(deserializer)? {
+        BoolOrInt::Bool(value) => Ok(value),
+        BoolOrInt::Int(value) => Ok(value != 0),
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[allow(non_snake_case)]
+pub struct DiskUsage {
+    pub LayersSize: i64,
+    pub Images: Option<Vec<DiskUsageImage>>,
+    pub Containers: Option<Vec<DiskUsageContainer>>,
+    pub Volumes: Option<Vec<Volume>>,
+    pub BuildCache: Option<Vec<BuildCache>>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// Sizes are `-1` when the daemon did not compute them.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[allow(non_snake_case)]
+pub struct DiskUsageImage {
+    pub Id: String,
+    pub ParentId: String,
+    pub RepoTags: Option<Vec<String>>,
+    pub RepoDigests: Option<Vec<String>>,
+    pub Created: i64,
+    pub Size: i64,
+    pub SharedSize: i64,
+    pub Labels: Option<HashMap<String, String>>,
+    pub Containers: i64,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[allow(non_snake_case)]
+pub struct DiskUsageContainer {
+    pub Id: String,
+    pub Names: Vec<String>,
+    pub Image: String,
+    pub ImageID: String,
+    pub Command: String,
+    pub Created: i64,
+    pub State: ContainerState,
+    pub Status: String,
+    pub SizeRw: Option<i64>,
+    pub SizeRootFs: Option<i64>,
+    pub Labels: Option<HashMap<String, String>>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[allow(non_snake_case)]
+pub struct BuildCache {
+    pub ID: String,
+    pub Parents: Option<Vec<String>>,
+    pub Type: String,
+    pub Description: Option<String>,
+    pub InUse: bool,
+    pub Shared: bool,
+    pub Size: i64,
+    pub CreatedAt: String,
+    pub LastUsedAt: Option<String>,
+    pub UsageCount: i64,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct UsageSummary {
+    pub total: u64,
+    pub active: u64,
+    pub size: i64,
+    pub reclaimable: i64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DiskUsageSummary {
+    pub images: UsageSummary,
+    pub containers: UsageSummary,
+    pub volumes: UsageSummary,
+    pub build_cache: UsageSummary,
+}
+
+impl DiskUsage {
+    /// Adds up sizes and reclaimable space by category, the same way
+    /// `docker system df` does.
+    pub fn summary(&self) -> DiskUsageSummary {
+        let mut summary = DiskUsageSummary::default();
+
+        summary.images.size = self.LayersSize;
+        let mut used = 0;
+        for image in self.Images.iter().flatten() {
+            summary.images.total += 1;
+            if image.Containers > 0 {
+                summary.images.active += 1;
+                if image.Size != -1 && image.SharedSize != -1 {
+                    used += image.Size - image.SharedSize;
+                }
+            }
+        }
+        summary.images.reclaimable = self.LayersSize - used;
+
+        for container in self.Containers.iter().flatten() {
+            let size = container.SizeRw.unwrap_or(0);
+            summary.containers.total += 1;
+            summary.containers.size += size;
+            // Paused and restarting containers still hold their layer.
+            if container.State.is_running() || container.State == ContainerState::Restarting {
+                summary.containers.active += 1;
+            } else {
+                summary.containers.reclaimable += size;
+            }
+        }
+
+        for volume in self.Volumes.iter().flatten() {
+            summary.volumes.total += 1;
+            let usage = match &volume.UsageData {
+                Some(usage) => usage,
+                None => continue,
+            };
+            if usage.RefCount > 0 {
+                summary.volumes.active += 1;
+            }
+            if usage.Size != -1 {
+                summary.volumes.size += usage.Size;
+                if usage.RefCount == 0 {
+                    summary.volumes.reclaimable += usage.Size;
+                }
+            }
+        }
+
+        for cache in self.BuildCache.iter().flatten() {
+            summary.build_cache.total += 1;
+            if cache.InUse {
+                summary.build_cache.active += 1;
+            }
+            if !cache.Shared {
+                summary.build_cache.size += cache.Size;
+                if !cache.InUse {
+                    summary.build_cache.reclaimable += cache.Size;
+                }
+            }
+        }
+
+        summary
+    }
+}
@@ -1,19 +1,64 @@
 #[cfg(test)]
-use crate::container::{Container, ContainerInfo};
+use crate::attach::AttachOptions;
 #[cfg(test)]
-use crate::filesystem::FilesystemChange;
+use crate::build::{self, BuildEvent, BuildStream};
 #[cfg(test)]
-use crate::image::Image;
+use crate::container::{
+    Container, ContainerCreate, ContainerInfo, ContainerListOptions, ContainerState, HealthStatus,
+    Healthcheck, ResourceUpdate, RestartPolicy, State,
+};
 #[cfg(test)]
-use crate::network::Network;
+use crate::error::ApiError;
+#[cfg(test)]
+use crate::event::Event;
+#[cfg(test)]
+use crate::exec::ExecOptions;
+#[cfg(test)]
+use crate::filesystem::{
+    diff_tree, filter_changes, unexpected_writes, ChangeCounts, ChangeKind, FilesystemChange,
+};
+#[cfg(test)]
+use crate::image::{Image, ImageLoad, ImageSearchFilters, ImageSearchResult, ImageStatus};
+#[cfg(test)]
+use crate::network::{
+    EndpointConfig, EndpointIpamConfig, IpamConfig, Network, NetworkCreate, NetworkPruneFilters,
+};
 #[cfg(test)]
 use crate::process::Top;
 #[cfg(test)]
+use crate::raw::RawBody;
+#[cfg(test)]
+use crate::retry::RetryPolicy;
+#[cfg(test)]
 use crate::stats::Stats;
 #[cfg(test)]
-use crate::system::SystemInfo;
+use crate::stdio::{Demux, StdStream};
+#[cfg(test)]
+use crate::system::{DiskUsage, SystemInfo, UsageSummary};
 #[cfg(test)]
 use crate::version::Version;
+#[cfg(test)]
+use crate::volume::{VolumeCreate, VolumeList, VolumeListOptions, VolumePruneFilters};
+#[cfg(test)]
+use crate::wait::{HealthTimeout, WaitCondition};
+#[cfg(test)]
+use crate::{Docker, Extra};
+#[cfg(test)]
+use std::collections::HashMap;
+#[cfg(test)]
+use std::io::{BufRead, BufReader, Read, Write};
+#[cfg(test)]
+use std::os::unix::net::{UnixListener, UnixStream};
+#[cfg(test)]
+use std::path::PathBuf;
+#[cfg(test)]
+use std::sync::atomic::{AtomicU32, Ordering};
+#[cfg(test)]
+use std::sync::Arc;
+#[cfg(test)]
+use std::thread::JoinHandle;
+#[cfg(test)]
+use std::time::{Duration, Instant};
 
 #[test]
 #[cfg(test)]
@@ -28,6 +73,157 @@ fn get_networks() {
     };
 }
 
+#[test]
+#[cfg(test)]
+fn inspect_network() {
+    let (socket, daemon) = fake_daemon_sequence(
+        "inspect_network",
+        vec![
+            ("200 OK", inspect_network_response()),
+            ("200 OK", inspect_network_response()),
+        ],
+    );
+    let mut docker = Docker::connect_to(socket.to_str().unwrap()).unwrap();
+    let network = docker
+        .inspect_network("overlay-net", true, Some("swarm"))
+        .unwrap();
+    docker.inspect_network("overlay-net", false, None).unwrap();
+    let requests = daemon.join().unwrap();
+    assert_eq!(
+        requests[0].0,
+        "GET /networks/overlay-net?verbose=true&scope=swarm HTTP/1.1"
+    );
+    assert_eq!(
+        requests[1].0,
+        "GET /networks/overlay-net?verbose=false HTTP/1.1"
+    );
+
+    let containers = network.Containers.unwrap();
+    let endpoint = &containers["19a4d5d687db25203351ed79d478946f861258f018fe384f229f2efa4b23513c"];
+    assert_eq!(endpoint.IPv4Address, "10.0.0.5/24");
+    assert_eq!(
+        network.IPAM.unwrap().Config.unwrap()[0].Gateway,
+        Some("10.0.0.1".to_string())
+    );
+    assert_eq!(
+        network.Services.unwrap()["web"]
+            .Tasks
+            .as_ref()
+            .unwrap()
+            .len(),
+        1
+    );
+}
+
+#[test]
+#[cfg(test)]
+fn create_network() {
+    let ipam = |subnet: &str, gateway: &str| IpamConfig {
+        Subnet: Some(subnet.to_string()),
+        IPRange: Some("172.20.10.0/24".to_string()),
+        Gateway: Some(gateway.to_string()),
+        ..Default::default()
+    };
+    assert!(NetworkCreate::builder("isolated_nw")
+        .ipam_config(ipam("172.20.0.0/16", "172.21.0.1"))
+        .build()
+        .is_err());
+    assert!(NetworkCreate::builder("isolated_nw")
+        .ipam_config(ipam("172.20.0.0/33", "172.20.0.1"))
+        .build()
+        .is_err());
+    let network = NetworkCreate::builder("isolated_nw")
+        .driver("bridge")
+        .label("com.example.job", "42")
+        .ipam_config(ipam("172.20.0.0/16", "172.20.0.1"))
+        .build()
+        .unwrap();
+
+    let (socket, daemon) = fake_daemon(
+        "create_network",
+        "201 Created",
+        "{\"Id\":\"22be93d5babb089c5aab8dbc369042fad48ff791584ca2da2100db837a1c7c30\",\"Warning\":\"\"}".to_string(),
+    );
+    let mut docker = Docker::connect_to(socket.to_str().unwrap()).unwrap();
+    let created = docker.create_network(network).unwrap();
+    let (_, body) = daemon.join().unwrap();
+    assert_eq!(
+        created.id,
+        "22be93d5babb089c5aab8dbc369042fad48ff791584ca2da2100db837a1c7c30"
+    );
+    assert_eq!(created.warning, None);
+    let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(body["IPAM"]["Driver"], "default");
+    assert_eq!(body["IPAM"]["Config"][0]["Gateway"], "172.20.0.1");
+}
+
+#[test]
+#[cfg(test)]
+fn connect_network() {
+    let (socket, daemon) = fake_daemon("connect_network", "200 OK", "".to_string());
+    let mut docker = Docker::connect_to(socket.to_str().unwrap()).unwrap();
+    let endpoint = EndpointConfig {
+        Aliases: Some(vec!["db".to_string()]),
+        Links: None,
+        IPAMConfig: Some(EndpointIpamConfig {
+            IPv4Address: Some("172.24.56.89".to_string()),
+            IPv6Address: None,
+            LinkLocalIPs: None,
+        }),
+    };
+    docker
+        .connect_network("isolated_nw", "3613f73ba0e4", Some(endpoint))
+        .unwrap();
+    let (request_line, body) = daemon.join().unwrap();
+    assert_eq!(request_line, "POST /networks/isolated_nw/connect HTTP/1.1");
+    let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(body["Container"], "3613f73ba0e4");
+    assert_eq!(
+        body["EndpointConfig"]["IPAMConfig"]["IPv4Address"],
+        "172.24.56.89"
+    );
+}
+
+#[test]
+#[cfg(test)]
+fn disconnect_network_not_found() {
+    let (socket, daemon) = fake_daemon(
+        "disconnect_network_not_found",
+        "404 Not Found",
+        "{\"message\":\"No such container: 3613f73ba0e4\"}".to_string(),
+    );
+    let mut docker = Docker::connect_to(socket.to_str().unwrap()).unwrap();
+    let e = docker
+        .disconnect_network("isolated_nw", "3613f73ba0e4", true)
+        .unwrap_err();
+    daemon.join().unwrap();
+    assert_eq!(e.kind(), std::io::ErrorKind::NotFound);
+    let api = ApiError::from_io(&e).unwrap();
+    assert!(api.is_not_found());
+    assert_eq!(api.message, "No such container: 3613f73ba0e4");
+}
+
+#[test]
+#[cfg(test)]
+fn prune_networks() {
+    let (socket, daemon) = fake_daemon(
+        "prune_networks",
+        "200 OK",
+        "{\"NetworksDeleted\":[\"ci-job-1312_default\",\"ci-job-1313_default\"]}".to_string(),
+    );
+    let mut docker = Docker::connect_to(socket.to_str().unwrap()).unwrap();
+    let filters = NetworkPruneFilters {
+        label: vec!["ci".to_string()],
+        ..Default::default()
+    };
+    let deleted = docker.prune_networks(&filters).unwrap();
+    assert_eq!(deleted, vec!["ci-job-1312_default", "ci-job-1313_default"]);
+    assert_eq!(
+        daemon.join().unwrap().0,
+        "POST /networks/prune?filters=%7B%22label%22%3A%5B%22ci%22%5D%7D HTTP/1.1"
+    );
+}
+
 #[test]
 #[cfg(test)]
 fn get_containers() {
@@ -41,6 +237,862 @@ fn get_containers() {
     };
 }
 
+#[test]
+#[cfg(test)]
+fn container_state() {
+    let containers: Vec<Container> = serde_json::from_str(&get_containers_response()).unwrap();
+    assert!(containers[0].State.is_none());
+    assert!(!containers[0].is_running());
+
+    let containers: Vec<Container> = serde_json::from_str(&get_containers_24_0_response()).unwrap();
+    let web = &containers[0];
+    assert_eq!(web.State, Some(ContainerState::Running));
+    assert!(web.is_running());
+    assert_eq!(
+        web.ImageID.as_deref(),
+        Some("sha256:a6bd71f48f6839d9faae1f29d3babef831e76bc213107682c5cc80f0cbb30866")
+    );
+    assert_eq!(
+        web.created_at(),
+        std::time::UNIX_EPOCH + Duration::from_secs(1682935200)
+    );
+    let mounts = web.Mounts.as_ref().unwrap();
+    assert_eq!(mounts[0].Name.as_deref(), Some("webdata"));
+    assert_eq!(mounts[0].Destination, "/usr/share/nginx/html");
+    assert!(!mounts[0].RW);
+    let networks = &web.NetworkSettings.as_ref().unwrap().Networks;
+    assert_eq!(networks["bridge"].IPAddress, "172.17.0.2");
+    assert_eq!(networks["bridge"].IPPrefixLen, 16);
+
+    assert_eq!(containers[1].State, Some(ContainerState::Paused));
+    assert!(containers[1].is_running());
+    assert_eq!(containers[1].SizeRootFs, None);
+
+    let state: ContainerState = serde_json::from_str("\"hibernating\"").unwrap();
+    assert_eq!(state, ContainerState::Unknown("hibernating".to_string()));
+    assert_eq!(serde_json::to_value(&state).unwrap(), "hibernating");
+}
+
+#[test]
+#[cfg(test)]
+fn extra_fields() {
+    let response = get_containers_response();
+    let containers: Vec<Container> = serde_json::from_str(&response).unwrap();
+    assert!(containers[0].extra.is_empty());
+
+    let response = get_container_info_response();
+    let info: ContainerInfo = serde_json::from_str(&response).unwrap();
+    assert_eq!(info.get_extra::<u64>("RestartCount"), None);
+    let config: serde_json::Value = info.get_extra("Config").unwrap();
+    assert_eq!(config["Hostname"], "a9de92dfbf97");
+    assert!(info.State.extra().is_empty());
+    assert_eq!(info.State.get_extra::<bool>("Running"), None);
+
+    let response = get_system_info_20_10_response();
+    let info: SystemInfo = serde_json::from_str(&response).unwrap();
+    assert_eq!(
+        info.get_extra::<String>("LoggingDriver"),
+        Some("json-file".to_string())
+    );
+    assert_eq!(info.get_extra::<u64>("LoggingDriver"), None);
+
+    let round_trip: serde_json::Value =
+        serde_json::from_str(&serde_json::to_string(&info).unwrap()).unwrap();
+    assert_eq!(round_trip["LoggingDriver"], "json-file");
+    assert_eq!(
+        round_trip["ContainerdCommit"]["ID"],
+        "d71fcd7d8303cbf684402823e425e9dd2e99285d"
+    );
+
+    let status: ImageStatus =
+        serde_json::from_str("{\"status\":\"Pulling\",\"id\":\"8d3ac3489996\"}").unwrap();
+    let copy = status.clone();
+    assert_eq!(copy.status, status.status);
+    assert_eq!(copy.error, None);
+    assert_eq!(
+        status.get_extra::<String>("id"),
+        Some("8d3ac3489996".to_string())
+    );
+
+    let load: ImageLoad = serde_json::from_str(
+        "{\"status\":\"Loading layer\",\"progressDetail\":{\"current\":1,\"total\":2,\"unit\":\"B\"},\"aux\":{\"ID\":\"x\"}}",
+    )
+    .unwrap();
+    assert!(load.get_extra::<serde_json::Value>("aux").is_some());
+    assert_eq!(
+        load.progressDetail.unwrap().get_extra::<String>("unit"),
+        Some("B".to_string())
+    );
+}
+
+#[test]
+#[cfg(test)]
+fn container_create_builder() {
+    assert!(ContainerCreate::builder("nginx")
+        .expose("80/http")
+        .build()
+        .is_err());
+    assert!(ContainerCreate::builder("nginx")
+        .expose("70000")
+        .build()
+        .is_err());
+    assert!(ContainerCreate::builder("nginx")
+        .publish("80", None, "eighty")
+        .build()
+        .is_err());
+    assert!(ContainerCreate::builder("nginx")
+        .publish("8000-8002", None, "9000-9001")
+        .build()
+        .is_err());
+
+    let container = ContainerCreate::builder("postgres:13")
+        .env("POSTGRES_PASSWORD", "secret")
+        .cmd(&["postgres", "-c", "fsync=off"])
+        .expose("5432")
+        .publish("5432/tcp", Some("127.0.0.1"), "15432")
+        .expose("8000-8010/udp")
+        .publish("7000-7001", None, "17000-17001")
+        .publish("9000", None, "19000-19010")
+        .bind("pgdata:/var/lib/postgresql/data")
+        .memory(512 * 1024 * 1024)
+        .pids_limit(100)
+        .restart_policy("on-failure", Some(3))
+        .cap_drop("ALL")
+        .healthcheck(Healthcheck {
+            Test: vec!["CMD-SHELL".to_string(), "pg_isready".to_string()],
+            Interval: Some(1_000_000_000),
+            Timeout: None,
+            Retries: Some(5),
+            StartPeriod: None,
+        })
+        .build()
+        .unwrap();
+
+    let body: serde_json::Value = serde_json::to_value(&container).unwrap();
+    assert_eq!(body["Env"][0], "POSTGRES_PASSWORD=secret");
+    assert_eq!(body["ExposedPorts"]["5432/tcp"], serde_json::json!({}));
+    assert_eq!(body["ExposedPorts"]["8000/udp"], serde_json::json!({}));
+    assert_eq!(body["ExposedPorts"]["8010/udp"], serde_json::json!({}));
+    assert!(body["ExposedPorts"].get("8000-8010/udp").is_none());
+    assert_eq!(body["ExposedPorts"].as_object().unwrap().len(), 15);
+    let bindings = &body["HostConfig"]["PortBindings"];
+    assert_eq!(bindings["7000/tcp"][0]["HostPort"], "17000");
+    assert_eq!(bindings["7001/tcp"][0]["HostPort"], "17001");
+    assert_eq!(bindings["9000/tcp"][0]["HostPort"], "19000-19010");
+    assert_eq!(
+        body["HostConfig"]["PortBindings"]["5432/tcp"][0]["HostPort"],
+        "15432"
+    );
+    assert_eq!(body["HostConfig"]["RestartPolicy"]["Name"], "on-failure");
+    assert_eq!(body["HostConfig"]["Memory"], 536870912);
+    assert_eq!(body["Healthcheck"]["Test"][1], "pg_isready");
+
+    // Options the caller left unset are not sent at all, rather than as
+    // nulls the daemon would read as explicit values.
+    let keys = |value: &serde_json::Value| -> Vec<String> {
+        let mut keys: Vec<String> = value.as_object().unwrap().keys().cloned().collect();
+        keys.sort();
+        keys
+    };
+    assert_eq!(
+        keys(&body),
+        vec![
+            "Cmd",
+            "Env",
+            "ExposedPorts",
+            "Healthcheck",
+            "HostConfig",
+            "Image"
+        ]
+    );
+    assert_eq!(
+        keys(&body["HostConfig"]),
+        vec![
+            "Binds",
+            "CapDrop",
+            "Memory",
+            "PidsLimit",
+            "PortBindings",
+            "RestartPolicy"
+        ]
+    );
+    let body: serde_json::Value =
+        serde_json::to_value(ContainerCreate::builder("alpine").build().unwrap()).unwrap();
+    assert_eq!(body, serde_json::json!({ "Image": "alpine" }));
+}
+
+#[test]
+#[cfg(test)]
+fn container_lifecycle() {
+    let (socket, daemon) = fake_daemon_sequence(
+        "container_lifecycle",
+        vec![
+            (
+                "201 Created",
+                "{\"Id\":\"e90e34656806\",\"Warnings\":[\"Your kernel does not support memory limit capabilities.\"]}".to_string(),
+            ),
+            ("204 No Content", "".to_string()),
+            ("304 Not Modified", "".to_string()),
+            ("204 No Content", "".to_string()),
+            ("304 Not Modified", "".to_string()),
+            ("204 No Content", "".to_string()),
+            ("204 No Content", "".to_string()),
+            ("204 No Content", "".to_string()),
+            ("204 No Content", "".to_string()),
+            (
+                "404 Not Found",
+                "{\"message\":\"No such container: gone\"}".to_string(),
+            ),
+            (
+                "409 Conflict",
+                "{\"message\":\"You cannot remove a running container e90e34656806. Stop the container before attempting removal or force remove\"}".to_string(),
+            ),
+        ],
+    );
+    let mut docker = Docker::connect_to(socket.to_str().unwrap()).unwrap();
+    let container = ContainerCreate::builder("postgres:13").build().unwrap();
+    let created = docker.create_container(Some("db"), container).unwrap();
+    assert_eq!(created.id, "e90e34656806");
+    assert_eq!(created.warnings.unwrap().len(), 1);
+
+    docker.start_container(&created.id).unwrap();
+
+    let e = docker.start_container(&created.id).unwrap_err();
+    assert!(ApiError::from_io(&e).unwrap().is_not_modified());
+
+    docker.stop_container(&created.id, Some(5)).unwrap();
+    let e = docker.stop_container(&created.id, None).unwrap_err();
+    assert!(ApiError::from_io(&e).unwrap().is_not_modified());
+
+    docker.restart_container(&created.id, Some(0)).unwrap();
+    docker.kill_container(&created.id, Some("SIGHUP")).unwrap();
+    docker.pause_container(&created.id).unwrap();
+    docker.unpause_container(&created.id).unwrap();
+
+    let e = docker.kill_container("gone", None).unwrap_err();
+    assert_eq!(e.kind(), std::io::ErrorKind::NotFound);
+    let api = ApiError::from_io(&e).unwrap();
+    assert!(api.is_not_found());
+    assert_eq!(api.message, "No such container: gone");
+
+    let e = docker
+        .remove_container(&created.id, false, true, false)
+        .unwrap_err();
+    assert_eq!(e.kind(), std::io::ErrorKind::AlreadyExists);
+    assert!(ApiError::from_io(&e).unwrap().is_conflict());
+
+    let requests: Vec<String> = daemon.join().unwrap().into_iter().map(|r| r.0).collect();
+    assert_eq!(
+        requests,
+        vec![
+            "POST /containers/create?name=db HTTP/1.1",
+            "POST /containers/e90e34656806/start HTTP/1.1",
+            "POST /containers/e90e34656806/start HTTP/1.1",
+            "POST /containers/e90e34656806/stop?t=5 HTTP/1.1",
+            "POST /containers/e90e34656806/stop HTTP/1.1",
+            "POST /containers/e90e34656806/restart?t=0 HTTP/1.1",
+            "POST /containers/e90e34656806/kill?signal=SIGHUP HTTP/1.1",
+            "POST /containers/e90e34656806/pause HTTP/1.1",
+            "POST /containers/e90e34656806/unpause HTTP/1.1",
+            "POST /containers/gone/kill HTTP/1.1",
+            "DELETE /containers/e90e34656806?force=false&v=true&link=false HTTP/1.1",
+        ]
+    );
+}
+
+#[test]
+#[cfg(test)]
+fn update_container() {
+    let (socket, daemon) = fake_daemon_sequence(
+        "update_container",
+        vec![
+            (
+                "200 OK",
+                "{\"Warnings\":[\"Your kernel does not support Block I/O weight. Weight discarded.\"]}".to_string(),
+            ),
+            ("200 OK", get_container_info_response()),
+            (
+                "200 OK",
+                get_container_info_response()
+                    .replace("\"CpusetCpus\":\"\"", "\"CpusetCpus\":\"0-1\"")
+                    .replace(
+                        "\"Memory\":0,\"MemorySwap\":0,\"NetworkMode\"",
+                        "\"Memory\":268435456,\"MemorySwap\":-1,\"NetworkMode\"",
+                    ),
+            ),
+        ],
+    );
+    let mut docker = Docker::connect_to(socket.to_str().unwrap()).unwrap();
+    let update = ResourceUpdate {
+        CpuQuota: Some(50000),
+        CpuPeriod: Some(100000),
+        CpusetCpus: Some("0-1".to_string()),
+        Memory: Some(256 * 1024 * 1024),
+        BlkioWeight: Some(300),
+        RestartPolicy: Some(RestartPolicy {
+            Name: "on-failure".to_string(),
+            MaximumRetryCount: Some(5),
+        }),
+        ..Default::default()
+    };
+    let updated = docker.update_container("web", update).unwrap();
+    assert_eq!(updated.warnings.unwrap().len(), 1);
+
+    let resources = docker.container_resources("web").unwrap();
+    assert_eq!(resources, ResourceUpdate::default());
+    assert_eq!(serde_json::to_string(&resources).unwrap(), "{}");
+
+    let resources = docker.container_resources("web").unwrap();
+    assert_eq!(
+        serde_json::to_value(&resources).unwrap(),
+        serde_json::json!({
+            "CpusetCpus": "0-1",
+            "Memory": 268435456,
+            "MemorySwap": -1,
+        })
+    );
+
+    let requests = daemon.join().unwrap();
+    assert_eq!(requests[0].0, "POST /containers/web/update HTTP/1.1");
+    let body: serde_json::Value = serde_json::from_str(&requests[0].1).unwrap();
+    assert_eq!(
+        body,
+        serde_json::json!({
+            "CpuQuota": 50000,
+            "CpuPeriod": 100000,
+            "CpusetCpus": "0-1",
+            "Memory": 268435456,
+            "BlkioWeight": 300,
+            "RestartPolicy": {"Name": "on-failure", "MaximumRetryCount": 5},
+        })
+    );
+    assert_eq!(requests[1].0, "GET /containers/web/json HTTP/1.1");
+    assert_eq!(requests[2].0, "GET /containers/web/json HTTP/1.1");
+}
+
+#[test]
+#[cfg(test)]
+fn wait_container() {
+    let (socket, daemon) = fake_daemon_sequence(
+        "wait_container",
+        vec![
+            (
+                "200 OK",
+                "{\"StatusCode\":137,\"Error\":{\"Message\":\"container was OOM killed\"}}"
+                    .to_string(),
+            ),
+            ("200 OK", "{\"StatusCode\":0,\"Error\":null}".to_string()),
+        ],
+    );
+    let mut docker = Docker::connect_to(socket.to_str().unwrap()).unwrap();
+    let exit = docker
+        .wait_container("db", WaitCondition::NotRunning)
+        .unwrap();
+    assert_eq!(exit.StatusCode, 137);
+    assert_eq!(exit.Error.unwrap().Message, "container was OOM killed");
+    let exit = docker.wait_container("db", WaitCondition::Removed).unwrap();
+    assert_eq!(exit.StatusCode, 0);
+    assert!(exit.Error.is_none());
+
+    let requests: Vec<String> = daemon.join().unwrap().into_iter().map(|r| r.0).collect();
+    assert_eq!(
+        requests,
+        vec![
+            "POST /containers/db/wait?condition=not-running HTTP/1.1",
+            "POST /containers/db/wait?condition=removed HTTP/1.1",
+        ]
+    );
+}
+
+#[test]
+#[cfg(test)]
+fn wait_healthy() {
+    let (socket, daemon) = fake_daemon_sequence(
+        "wait_healthy",
+        vec![
+            ("200 OK", health_response("starting", 0)),
+            ("200 OK", health_response("healthy", 1)),
+            ("200 OK", health_response("unhealthy", 4)),
+            ("200 OK", container_info_response(true, false, None)),
+            ("200 OK", container_info_response(false, false, None)),
+        ],
+    );
+    let mut docker = Docker::connect_to(socket.to_str().unwrap())
+        .unwrap()
+        .with_health_poll_interval(Duration::from_millis(10));
+    let health = docker.wait_healthy("db", Duration::from_secs(5)).unwrap();
+    assert!(health.is_healthy());
+
+    // Already past the deadline after the first inspection, whatever the
+    // timing of the test run.
+    let e = docker
+        .wait_healthy("db", Duration::from_millis(0))
+        .unwrap_err();
+    assert_eq!(e.kind(), std::io::ErrorKind::TimedOut);
+    let timeout = HealthTimeout::from_io(&e).unwrap();
+    assert_eq!(timeout.health.FailingStreak, 4);
+    assert_eq!(
+        e.to_string(),
+        "container still unhealthy after 0ns\n  exit code 1: check 2 failed\n  exit code 1: check 3 failed\n  exit code 1: check 4 failed"
+    );
+
+    let e = docker
+        .wait_healthy("db", Duration::from_secs(5))
+        .unwrap_err();
+    assert_eq!(e.kind(), std::io::ErrorKind::InvalidInput);
+    let e = docker
+        .wait_healthy("db", Duration::from_secs(5))
+        .unwrap_err();
+    assert_eq!(e.to_string(), "container db is not running");
+    assert_eq!(daemon.join().unwrap().len(), 5);
+}
+
+#[test]
+#[cfg(test)]
+fn exec() {
+    let (socket, daemon) = fake_daemon_sequence(
+        "exec",
+        vec![
+            ("201 Created", "{\"Id\":\"4d3c1a\"}".to_string()),
+            ("200 OK", exec_output_response()),
+            (
+                "200 OK",
+                "{\"ID\":\"4d3c1a\",\"ContainerID\":\"e90e34656806\",\"Running\":false,\"ExitCode\":3,\"Pid\":4242}".to_string(),
+            ),
+        ],
+    );
+    let mut docker = Docker::connect_to(socket.to_str().unwrap()).unwrap();
+    let mut options = ExecOptions::new(&["pg_isready", "-q"]);
+    options.user = Some("postgres".to_string());
+    let output = docker.exec("db", &options).unwrap();
+    assert_eq!(output.stdout, b"accepting\n");
+    assert_eq!(output.stderr, b"no response\n");
+    assert_eq!(output.exit_code, Some(3));
+    assert!(!output.success());
+
+    let requests = daemon.join().unwrap();
+    assert_eq!(requests[0].0, "POST /containers/db/exec HTTP/1.1");
+    let config: serde_json::Value = serde_json::from_str(&requests[0].1).unwrap();
+    assert_eq!(config["Cmd"][0], "pg_isready");
+    assert_eq!(config["User"], "postgres");
+    assert_eq!(config["AttachStdin"], false);
+    assert!(config.get("Env").is_none());
+    assert_eq!(requests[1].0, "POST /exec/4d3c1a/start HTTP/1.1");
+    assert_eq!(requests[1].1, "{\"Detach\":false,\"Tty\":false}");
+    assert_eq!(requests[2].0, "GET /exec/4d3c1a/json HTTP/1.1");
+}
+
+#[test]
+#[cfg(test)]
+fn exec_stream_stdin() {
+    let (socket, listener) = silent_daemon("exec_stream_stdin");
+    let daemon = std::thread::spawn(move || {
+        let (mut conn, _, _) = accept_request(&listener);
+        write!(
+            conn.get_mut(),
+            "HTTP/1.1 201 Created\r\nContent-Length: 13\r\nConnection: close\r\n\r\n{{\"Id\":\"77aa\"}}"
+        )
+        .unwrap();
+
+        // Echo stdin back as stdout frames until it is closed.
+        let (mut conn, request_line, _) = accept_request(&listener);
+        write!(
+            conn.get_mut(),
+            "HTTP/1.1 101 UPGRADED\r\nConnection: Upgrade\r\nUpgrade: tcp\r\n\r\n"
+        )
+        .unwrap();
+        let mut input = Vec::new();
+        conn.read_to_end(&mut input).unwrap();
+        let mut frame = vec![1, 0, 0, 0];
+        frame.extend((input.len() as u32).to_be_bytes());
+        frame.extend(input);
+        conn.get_mut().write_all(&frame).unwrap();
+        drop(conn);
+
+        let (mut conn, _, _) = accept_request(&listener);
+        write!(
+            conn.get_mut(),
+            "HTTP/1.1 200 OK\r\nContent-Length: 61\r\nConnection: close\r\n\r\n{{\"ID\":\"77aa\",\"ContainerID\":\"db\",\"Running\":false,\"ExitCode\":0}}"
+        )
+        .unwrap();
+        request_line
+    });
+
+    let mut docker = Docker::connect_to(socket.to_str().unwrap()).unwrap();
+    let session = docker
+        .exec_stream("db", &ExecOptions::new(&["cat"]))
+        .unwrap();
+    assert_eq!(session.id(), "77aa");
+    let mut stdin = session.stdin().unwrap();
+    stdin.write_all(b"select 1;").unwrap();
+    let output = session.finish(&mut docker).unwrap();
+    assert_eq!(output.stdout, b"select 1;");
+    assert!(output.stderr.is_empty());
+    assert!(output.success());
+    assert_eq!(daemon.join().unwrap(), "POST /exec/77aa/start HTTP/1.1");
+}
+
+#[test]
+#[cfg(test)]
+fn demux_tty() {
+    let output = "\u{1b}[1mbold\u{1b}[0m".as_bytes();
+    let frames: Vec<(StdStream, Vec<u8>)> = Demux::new(output, true)
+        .map(|frame| frame.unwrap())
+        .collect();
+    assert_eq!(frames, vec![(StdStream::Stdout, output.to_vec())]);
+
+    let mut truncated = exec_output_response().into_bytes();
+    truncated.truncate(12);
+    assert!(Demux::new(&truncated[..], false).collect_output().is_err());
+}
+
+#[test]
+#[cfg(test)]
+fn attach() {
+    let (socket, listener) = silent_daemon("attach");
+    let daemon = std::thread::spawn(move || {
+        let mut requests = Vec::new();
+        let (mut conn, request_line, _) = accept_request(&listener);
+        let info = container_info_response(true, false, None);
+        write!(
+            conn.get_mut(),
+            "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            info.len(),
+            info
+        )
+        .unwrap();
+        requests.push(request_line);
+
+        let (mut conn, request_line, _) = accept_request(&listener);
+        write!(
+            conn.get_mut(),
+            "HTTP/1.1 101 UPGRADED\r\nContent-Type: application/vnd.docker.raw-stream\r\nConnection: Upgrade\r\nUpgrade: tcp\r\n\r\n"
+        )
+        .unwrap();
+        conn.get_mut()
+            .write_all(exec_output_response().as_bytes())
+            .unwrap();
+        let mut input = Vec::new();
+        conn.read_to_end(&mut input).unwrap();
+        let mut frame = vec![1, 0, 0, 0];
+        frame.extend((input.len() as u32).to_be_bytes());
+        frame.extend(input);
+        conn.get_mut().write_all(&frame).unwrap();
+        drop(conn);
+        requests.push(request_line);
+
+        let (mut conn, request_line, _) = accept_request(&listener);
+        write!(
+            conn.get_mut(),
+            "HTTP/1.1 200 OK\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+        )
+        .unwrap();
+        requests.push(request_line);
+        requests
+    });
+
+    let mut docker = Docker::connect_to(socket.to_str().unwrap()).unwrap();
+    let options = AttachOptions {
+        detach_keys: Some("ctrl-p,ctrl-q".to_string()),
+        ..Default::default()
+    };
+    let mut attached = docker.attach("db", &options).unwrap();
+    attached.stdin.write_all(b"ping\n").unwrap();
+    attached.stdin.close().unwrap();
+    let mut stdout = String::new();
+    attached.stdout.read_to_string(&mut stdout).unwrap();
+    assert_eq!(stdout, "accepting\nping\n");
+    let mut stderr = String::new();
+    attached.stderr.read_to_string(&mut stderr).unwrap();
+    assert_eq!(stderr, "no response\n");
+
+    docker.resize_container("db", 40, 120).unwrap();
+
+    assert_eq!(
+        daemon.join().unwrap(),
+        vec![
+            "GET /containers/db/json HTTP/1.1",
+            "POST /containers/db/attach?stream=1&stdin=1&stdout=1&stderr=1&logs=0&detachKeys=ctrl-p%2Cctrl-q HTTP/1.1",
+            "POST /containers/db/resize?h=40&w=120 HTTP/1.1",
+        ]
+    );
+}
+
+#[test]
+#[cfg(test)]
+fn attach_tty_detach() {
+    let (socket, listener) = silent_daemon("attach_tty_detach");
+    let daemon = std::thread::spawn(move || {
+        let (mut conn, _, _) = accept_request(&listener);
+        let info = container_info_response(true, true, None);
+        write!(
+            conn.get_mut(),
+            "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            info.len(),
+            info
+        )
+        .unwrap();
+
+        // Raw output is passed through as is, even if it looks like a frame.
+        let (mut conn, _, _) = accept_request(&listener);
+        write!(
+            conn.get_mut(),
+            "HTTP/1.1 101 UPGRADED\r\nConnection: Upgrade\r\nUpgrade: tcp\r\n\r\n"
+        )
+        .unwrap();
+        conn.get_mut()
+            .write_all(exec_output_response().as_bytes())
+            .unwrap();
+        let mut input = Vec::new();
+        conn.read_to_end(&mut input).unwrap();
+    });
+
+    let mut docker = Docker::connect_to(socket.to_str().unwrap()).unwrap();
+    let mut attached = docker.attach("db", &AttachOptions::default()).unwrap();
+    let mut output = vec![0; exec_output_response().len()];
+    attached.stdout.read_exact(&mut output).unwrap();
+    assert_eq!(output, exec_output_response().into_bytes());
+
+    attached.cancel_handle().cancel();
+    let mut rest = Vec::new();
+    attached.stdout.read_to_end(&mut rest).unwrap();
+    assert!(rest.is_empty());
+    attached.stderr.read_to_end(&mut rest).unwrap();
+    assert!(rest.is_empty());
+    daemon.join().unwrap();
+}
+
+#[test]
+#[cfg(test)]
+fn stat_path() {
+    let (socket, listener) = silent_daemon("stat_path");
+    let daemon = std::thread::spawn(move || {
+        let (mut conn, request_line, _) = accept_request(&listener);
+        write!(
+            conn.get_mut(),
+            "HTTP/1.1 200 OK\r\nX-Docker-Container-Path-Stat: {}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+            stat_path_response()
+        )
+        .unwrap();
+        let (mut conn, _, _) = accept_request(&listener);
+        write!(
+            conn.get_mut(),
+            "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+        )
+        .unwrap();
+        request_line
+    });
+
+    let mut docker = Docker::connect_to(socket.to_str().unwrap()).unwrap();
+    let stat = docker.stat_path("web", "/etc/nginx").unwrap();
+    assert_eq!(stat.name, "nginx");
+    assert!(stat.is_dir());
+    assert!(!stat.is_symlink());
+    assert_eq!(stat.permissions(), 0o755);
+
+    let e = docker.stat_path("web", "/etc/missing").unwrap_err();
+    assert!(ApiError::from_io(&e).unwrap().is_not_found());
+    assert_eq!(
+        daemon.join().unwrap(),
+        "HEAD /containers/web/archive?path=%2Fetc%2Fnginx HTTP/1.1"
+    );
+}
+
+#[test]
+#[cfg(test)]
+fn download_path() {
+    let mut builder = tar::Builder::new(Vec::new());
+    let config = b"worker_processes 1;\n";
+    let mut header = tar::Header::new_gnu();
+    header.set_size(config.len() as u64);
+    header.set_mode(0o644);
+    header.set_cksum();
+    builder
+        .append_data(&mut header, "nginx/nginx.conf", &config[..])
+        .unwrap();
+    let archive = String::from_utf8(builder.into_inner().unwrap()).unwrap();
+    let (socket, daemon) = fake_daemon("download_path", "200 OK", archive);
+
+    let mut docker = Docker::connect_to(socket.to_str().unwrap()).unwrap();
+    let mut stream = docker.download_path("web", "/etc/nginx").unwrap();
+    let mut files = Vec::new();
+    for entry in stream.entries().unwrap() {
+        let mut entry = entry.unwrap();
+        let mut contents = String::new();
+        entry.read_to_string(&mut contents).unwrap();
+        files.push((entry.path().unwrap().display().to_string(), contents));
+    }
+    assert_eq!(
+        files,
+        vec![(
+            "nginx/nginx.conf".to_string(),
+            "worker_processes 1;\n".to_string()
+        )]
+    );
+    // Once walked, the stream belongs to the archive and is no longer raw.
+    assert!(stream.read(&mut [0; 16]).is_err());
+    assert_eq!(
+        daemon.join().unwrap().0,
+        "GET /containers/web/archive?path=%2Fetc%2Fnginx HTTP/1.1"
+    );
+}
+
+#[test]
+#[cfg(test)]
+fn export_container() {
+    let binary: Vec<u8> = (0..300).map(|i| (i * 7 % 256) as u8).collect();
+    let mut builder = tar::Builder::new(Vec::new());
+    let mut header = tar::Header::new_gnu();
+    header.set_entry_type(tar::EntryType::Directory);
+    header.set_size(0);
+    header.set_mode(0o755);
+    header.set_cksum();
+    builder
+        .append_data(&mut header, "usr/", std::io::empty())
+        .unwrap();
+    for (path, contents) in [("etc/hostname", &b"web\n"[..]), ("usr/bin/app", &binary)] {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(contents.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder.append_data(&mut header, path, contents).unwrap();
+    }
+    let archive = builder.into_inner().unwrap();
+
+    let (socket, listener) = silent_daemon("export_container");
+    let served = archive.clone();
+    let daemon = std::thread::spawn(move || {
+        let mut requests = Vec::new();
+        for _ in 0..3 {
+            let (mut conn, request_line, _) = accept_request(&listener);
+            write!(
+                conn.get_mut(),
+                "HTTP/1.1 200 OK\r\nContent-Type: application/x-tar\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                served.len()
+            )
+            .unwrap();
+            conn.get_mut().write_all(&served).unwrap();
+            requests.push(request_line);
+        }
+        requests
+    });
+
+    let mut docker = Docker::connect_to(socket.to_str().unwrap()).unwrap();
+    let mut exported = Vec::new();
+    docker
+        .export_container("web")
+        .unwrap()
+        .read_to_end(&mut exported)
+        .unwrap();
+    assert_eq!(exported, archive);
+
+    let app = docker
+        .export_container("web")
+        .unwrap()
+        .read_file("/usr/bin/app")
+        .unwrap();
+    assert_eq!(app, Some(binary));
+
+    let sizes = docker
+        .export_container("web")
+        .unwrap()
+        .directory_sizes()
+        .unwrap();
+    assert_eq!(sizes[""], 304);
+    assert_eq!(sizes["etc"], 4);
+    assert_eq!(sizes["usr"], 300);
+    assert_eq!(sizes["usr/bin"], 300);
+
+    assert_eq!(
+        daemon.join().unwrap(),
+        vec!["GET /containers/web/export HTTP/1.1"; 3]
+    );
+}
+
+#[test]
+#[cfg(test)]
+fn upload_path() {
+    let (socket, listener) = silent_daemon("upload_path");
+    let daemon = std::thread::spawn(move || {
+        let (mut conn, request_line, _) = accept_request(&listener);
+        // The archive is streamed, so it comes chunked.
+        let mut body: Vec<u8> = Vec::new();
+        let mut line = String::new();
+        loop {
+            line.clear();
+            conn.read_line(&mut line).unwrap();
+            let size = usize::from_str_radix(line.trim(), 16).unwrap();
+            let mut chunk = vec![0; size + 2];
+            conn.read_exact(&mut chunk).unwrap();
+            if size == 0 {
+                break;
+            }
+            body.extend(&chunk[..size]);
+        }
+        write!(
+            conn.get_mut(),
+            "HTTP/1.1 200 OK\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+        )
+        .unwrap();
+        (request_line, body)
+    });
+
+    let mut docker = Docker::connect_to(socket.to_str().unwrap()).unwrap();
+    let archive = b"not really a tar archive".to_vec();
+    docker
+        .upload_path("web", "/etc/nginx", std::io::Cursor::new(archive), true)
+        .unwrap();
+    let (request_line, body) = daemon.join().unwrap();
+    assert_eq!(
+        request_line,
+        "PUT /containers/web/archive?path=%2Fetc%2Fnginx&noOverwriteDirNonDir=true HTTP/1.1"
+    );
+    assert_eq!(body, b"not really a tar archive");
+}
+
+#[test]
+#[cfg(test)]
+fn get_events() {
+    let (socket, daemon) = fake_daemon("get_events", "200 OK", get_events_response());
+    let mut docker = Docker::connect_to(socket.to_str().unwrap()).unwrap();
+    let events = docker
+        .get_events(
+            Some("1629574600".to_string()),
+            Some("2021-08-21T19:38:20Z".to_string()),
+        )
+        .unwrap();
+    assert_eq!(
+        daemon.join().unwrap().0,
+        "GET /events?since=1629574600&until=2021-08-21T19%3A38%3A20Z HTTP/1.1"
+    );
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0].status.as_deref(), Some("start"));
+    assert_eq!(events[0].id.as_deref(), Some("3ae23f4a8b1c"));
+    assert_eq!(events[0].time, 1629574695);
+    assert_eq!(events[0].timeNano, Some(1629574695515050031));
+    assert_eq!(events[1].Type, "network");
+    assert_eq!(events[1].status, None);
+    assert_eq!(events[1].id, None);
+    assert_eq!(events[1].Actor.ID, "7d86d31b1478");
+}
+
+#[test]
+#[cfg(test)]
+fn get_stats_once() {
+    let (socket, daemon) = fake_daemon("get_stats_once", "200 OK", get_stats_response());
+    let mut docker = Docker::connect_to(socket.to_str().unwrap()).unwrap();
+    let containers: Vec<Container> = serde_json::from_str(&get_containers_24_0_response()).unwrap();
+    let stats = docker.get_stats(&containers[0]).unwrap();
+    assert_eq!(stats.read, "2015-04-09T07:02:08.480022082Z");
+    assert_eq!(
+        daemon.join().unwrap().0,
+        format!(
+            "GET /containers/{}/stats?stream=false HTTP/1.1",
+            containers[0].Id
+        )
+    );
+}
+
 #[test]
 #[cfg(test)]
 fn get_stats() {
@@ -54,6 +1106,54 @@ fn get_stats() {
     };
 }
 
+#[test]
+#[cfg(test)]
+fn container_health() {
+    let info: ContainerInfo = serde_json::from_str(&get_container_info_response()).unwrap();
+    assert!(info.State.Running);
+    assert_eq!(info.State.Pid, 891);
+    assert!(info.State.Health.is_none());
+
+    let state: State = serde_json::from_str(&container_state_response()).unwrap();
+    assert_eq!(state.Status.as_deref(), Some("running"));
+    let health = state.Health.unwrap();
+    assert_eq!(health.Status, HealthStatus::Unhealthy);
+    assert_eq!(health.FailingStreak, 2);
+    let probes = health.last_probes(2);
+    assert_eq!(probes.len(), 2);
+    assert_eq!(probes[1].ExitCode, 1);
+    assert_eq!(probes[1].Output, "connection refused\n");
+    assert_eq!(health.last_probes(10).len(), 3);
+    assert_eq!(
+        serde_json::from_str::<HealthStatus>("\"degraded\"").unwrap(),
+        HealthStatus::Unknown("degraded".to_string())
+    );
+
+    let (socket, daemon) = fake_daemon("list_containers", "200 OK", list_containers_response());
+    let mut docker = Docker::connect_to(socket.to_str().unwrap()).unwrap();
+    let options = ContainerListOptions {
+        all: true,
+        health: vec![HealthStatus::Unhealthy],
+        ..Default::default()
+    };
+    let containers = docker.list_containers(&options).unwrap();
+    assert_eq!(containers.len(), 1);
+    assert_eq!(containers[0].Names, vec!["/api".to_string()]);
+    assert_eq!(containers[0].SizeRw, None);
+    assert_eq!(containers[0].SizeRootFs, None);
+    assert_eq!(
+        daemon.join().unwrap().0,
+        "GET /containers/json?all=true&size=false&filters=%7B%22health%22%3A%5B%22unhealthy%22%5D%7D HTTP/1.1"
+    );
+
+    // A stopped container is rejected from its listed state, without asking
+    // the daemon.
+    let mut containers: Vec<Container> = serde_json::from_str(&get_containers_response()).unwrap();
+    containers[0].State = Some(ContainerState::Exited);
+    let e = docker.get_stats(&containers[0]).unwrap_err();
+    assert_eq!(e.kind(), std::io::ErrorKind::InvalidInput);
+}
+
 #[test]
 #[cfg(test)]
 fn get_system_info() {
@@ -67,6 +1167,66 @@ fn get_system_info() {
     };
 }
 
+#[test]
+#[cfg(test)]
+fn get_system_info_versions() {
+    let legacy: SystemInfo = serde_json::from_str(&get_system_info_response()).unwrap();
+    assert!(legacy.MemoryLimit);
+    assert!(!legacy.Debug);
+    assert_eq!(legacy.ExecutionDriver, Some("native-0.2".to_string()));
+
+    let v20: SystemInfo = match serde_json::from_str(&get_system_info_20_10_response()) {
+        Ok(body) => body,
+        Err(e) => panic!("{}", e),
+    };
+    assert!(!v20.SwapLimit);
+    assert_eq!(v20.InitPath, None);
+    assert_eq!(v20.CgroupVersion, Some("1".to_string()));
+    assert_eq!(v20.ContainersStopped, Some(10));
+
+    let v24: SystemInfo = match serde_json::from_str(&get_system_info_24_0_response()) {
+        Ok(body) => body,
+        Err(e) => panic!("{}", e),
+    };
+    assert_eq!(v24.ServerVersion, Some("24.0.6".to_string()));
+    assert_eq!(v24.Swarm.unwrap().LocalNodeState, "active");
+    let registry = v24.RegistryConfig.unwrap();
+    assert!(registry.IndexConfigs.unwrap()["docker.io"].Official);
+}
+
+#[test]
+#[cfg(test)]
+fn disk_usage() {
+    let response = disk_usage_response();
+    let usage: DiskUsage = match serde_json::from_str(&response) {
+        Ok(body) => body,
+        Err(e) => panic!("{}", e),
+    };
+    let summary = usage.summary();
+    assert_eq!(
+        summary.images,
+        UsageSummary {
+            total: 2,
+            active: 1,
+            size: 1092588,
+            reclaimable: 0,
+        }
+    );
+    assert_eq!(
+        summary.containers,
+        UsageSummary {
+            total: 2,
+            active: 1,
+            size: 112,
+            reclaimable: 12,
+        }
+    );
+    assert_eq!(summary.volumes.size, 8432);
+    assert_eq!(summary.volumes.reclaimable, 1024);
+    assert_eq!(summary.build_cache.size, 51);
+    assert_eq!(summary.build_cache.reclaimable, 51);
+}
+
 #[test]
 #[cfg(test)]
 fn get_images() {
@@ -110,13 +1270,66 @@ fn get_processes() {
 #[cfg(test)]
 fn get_filesystem_changes() {
     let response = get_filesystem_changes_response();
-    let _: Vec<FilesystemChange> = match serde_json::from_str(&response) {
+    let changes: Vec<FilesystemChange> = match serde_json::from_str(&response) {
         Ok(body) => body,
         Err(_) => {
             assert!(false);
             return;
         }
     };
+    assert_eq!(changes[0].Kind, ChangeKind::Modified);
+}
+
+#[test]
+#[cfg(test)]
+fn filesystem_diff() {
+    let changes: Vec<FilesystemChange> = match serde_json::from_str(&filesystem_diff_response()) {
+        Ok(changes) => changes,
+        Err(e) => panic!("{}", e),
+    };
+    assert_eq!(changes[2].Kind, ChangeKind::Added);
+    assert_eq!(changes[7].Kind, ChangeKind::Unknown(7));
+    assert_eq!(changes[5].Kind.to_string(), "D");
+    assert_eq!(serde_json::to_value(&changes[5]).unwrap()["Kind"], 2);
+
+    let tree = diff_tree(&changes);
+    assert_eq!(tree.counts.total(), 7);
+    let log = tree.get("/var/log").unwrap();
+    assert_eq!(log.kind, Some(ChangeKind::Modified));
+    assert_eq!(
+        log.counts,
+        ChangeCounts {
+            modified: 1,
+            added: 2,
+            deleted: 0
+        }
+    );
+    assert_eq!(
+        log.children.keys().collect::<Vec<_>>(),
+        vec!["nginx", "nginx.log"]
+    );
+    assert_eq!(tree.get("/etc").unwrap().counts.deleted, 1);
+    assert!(tree.get("/usr").is_none());
+
+    let paths = |changes: Vec<&FilesystemChange>| -> Vec<String> {
+        changes.iter().map(|c| c.Path.clone()).collect()
+    };
+    assert_eq!(
+        paths(filter_changes(&changes, &["/var/log"])),
+        vec!["/var/log", "/var/log/nginx", "/var/log/nginx.log"]
+    );
+    assert_eq!(
+        paths(filter_changes(&changes, &["**/*.log", "etc/*.conf"])),
+        vec!["/var/log/nginx.log", "/etc/resolv.conf"]
+    );
+
+    assert_eq!(
+        paths(unexpected_writes(
+            &changes,
+            &["/var/log", "/var/lib/app/data"]
+        )),
+        vec!["/etc", "/etc/resolv.conf", "/tmp/x"]
+    );
 }
 
 #[test]
@@ -132,6 +1345,637 @@ fn get_version() {
     };
 }
 
+#[test]
+#[cfg(test)]
+fn save_images() {
+    // A tarball is not UTF-8, so the daemon writes raw bytes rather than
+    // going through fake_daemon's string body.
+    let tarball: Vec<u8> = vec![0x75, 0x73, 0x74, 0x61, 0x72, 0x00, 0xff, 0xfe, 0x00, 0x80];
+    let (socket, listener) = silent_daemon("save_images");
+    let sent = tarball.clone();
+    let daemon = std::thread::spawn(move || {
+        let (mut conn, request_line, _) = accept_request(&listener);
+        write!(
+            conn.get_mut(),
+            "HTTP/1.1 200 OK\r\nContent-Type: application/x-tar\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+            sent.len()
+        )
+        .unwrap();
+        conn.get_mut().write_all(&sent).unwrap();
+        request_line
+    });
+
+    let mut docker = Docker::connect_to(socket.to_str().unwrap()).unwrap();
+    let mut saved = Vec::new();
+    docker
+        .save_images(&["alpine:3.14", "busybox"])
+        .unwrap()
+        .read_to_end(&mut saved)
+        .unwrap();
+    assert_eq!(saved, tarball);
+    assert_eq!(
+        daemon.join().unwrap(),
+        "GET /images/get?names=alpine%3A3.14&names=busybox HTTP/1.1"
+    );
+}
+
+#[test]
+#[cfg(test)]
+fn load_image() {
+    // Serves `response` to a single image load, returning the request line,
+    // the request headers and the (chunked) tarball that was uploaded.
+    fn load_daemon(
+        name: &str,
+        response: String,
+    ) -> (PathBuf, JoinHandle<(String, String, Vec<u8>)>) {
+        let (socket, listener) = silent_daemon(name);
+        let daemon = std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut conn = BufReader::new(stream);
+            let mut request_line = String::new();
+            conn.read_line(&mut request_line).unwrap();
+            let mut headers = String::new();
+            let mut line = String::new();
+            while conn.read_line(&mut line).unwrap() > 2 {
+                headers.push_str(&line);
+                line.clear();
+            }
+            let mut body: Vec<u8> = Vec::new();
+            loop {
+                line.clear();
+                conn.read_line(&mut line).unwrap();
+                let size = usize::from_str_radix(line.trim(), 16).unwrap();
+                let mut chunk = vec![0; size + 2];
+                conn.read_exact(&mut chunk).unwrap();
+                if size == 0 {
+                    break;
+                }
+                body.extend(&chunk[..size]);
+            }
+            write!(
+                conn.get_mut(),
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                response.len(),
+                response
+            )
+            .unwrap();
+            (request_line.trim_end().to_string(), headers, body)
+        });
+        (socket, daemon)
+    }
+
+    let (socket, daemon) = load_daemon("load_image", load_image_response());
+    let mut docker = Docker::connect_to(socket.to_str().unwrap()).unwrap();
+    let tarball = b"not really a tar archive".to_vec();
+    let messages = docker
+        .load_image(std::io::Cursor::new(tarball), false)
+        .unwrap();
+    let (request_line, headers, body) = daemon.join().unwrap();
+    assert_eq!(request_line, "POST /images/load?quiet=0 HTTP/1.1");
+    assert!(headers
+        .lines()
+        .any(|header| header.eq_ignore_ascii_case("Content-Type: application/x-tar")));
+    assert_eq!(body, b"not really a tar archive");
+
+    assert_eq!(messages.len(), 3);
+    let progress = messages[0].progressDetail.as_ref().unwrap();
+    assert_eq!(progress.current, Some(32768));
+    assert_eq!(progress.total, Some(5820416));
+    let progress = messages[1].progressDetail.as_ref().unwrap();
+    assert_eq!(progress.current, progress.total);
+    assert_eq!(
+        messages[2].stream.as_deref(),
+        Some("Loaded image: alpine:3.14\n")
+    );
+
+    let (socket, daemon) = load_daemon(
+        "load_image_error",
+        "{\"errorDetail\":{\"message\":\"unexpected EOF\"},\"error\":\"unexpected EOF\"}\r\n"
+            .to_string(),
+    );
+    let mut docker = Docker::connect_to(socket.to_str().unwrap()).unwrap();
+    let err = docker
+        .load_image(std::io::Cursor::new(b"truncated".to_vec()), true)
+        .unwrap_err();
+    let (request_line, _, _) = daemon.join().unwrap();
+    assert_eq!(request_line, "POST /images/load?quiet=1 HTTP/1.1");
+    assert_eq!(err.to_string(), "unexpected EOF");
+}
+
+#[test]
+#[cfg(test)]
+fn build_image() {
+    let response = build_image_response();
+    let events: Vec<BuildEvent> = match BuildStream::new(response.as_bytes()).collect() {
+        Ok(events) => events,
+        Err(e) => panic!("{}", e),
+    };
+    assert_eq!(
+        events[0],
+        BuildEvent::Stream("Step 1/2 : FROM alpine:3.14".to_string())
+    );
+    assert_eq!(
+        events[3],
+        BuildEvent::Image(
+            "sha256:1fd3d6a3e7a5b5e0e4c2b55d8f8c4a2a6bd5ff7f2e7c2f5fe0b2a4d3e1d1c0f9".to_string()
+        )
+    );
+    assert_eq!(
+        events[4],
+        BuildEvent::Error("The command '/bin/sh -c false' returned a non-zero code: 1".to_string())
+    );
+    match &events[5] {
+        BuildEvent::Aux(aux) => {
+            assert!(aux.ID.is_none());
+            assert_eq!(aux.get_extra::<u64>("Size"), Some(528));
+        }
+        other => panic!("unexpected event {:?}", other),
+    }
+}
+
+#[test]
+#[cfg(test)]
+fn dockerignore() {
+    let ignore =
+        build::parse_dockerignore("# comment\n*.md\n!README.md\n/target\n**/*.log\ntmp?\n");
+    assert!(build::is_excluded(&ignore, "CHANGELOG.md"));
+    assert!(!build::is_excluded(&ignore, "README.md"));
+    assert!(build::is_excluded(&ignore, "target/debug/build"));
+    assert!(build::is_excluded(&ignore, "logs/app/out.log"));
+    assert!(build::is_excluded(&ignore, "tmp1"));
+    assert!(!build::is_excluded(&ignore, "src/main.rs"));
+    assert!(!build::is_excluded(&ignore, "docs/CHANGELOG.md"));
+}
+
+#[test]
+#[cfg(test)]
+fn tar_context_ignored_dockerfile() {
+    let dir = std::env::temp_dir().join(format!("docker-sync-tar-context-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(dir.join("docker")).unwrap();
+    std::fs::write(dir.join(".dockerignore"), "docker\n").unwrap();
+    std::fs::write(dir.join("docker/Dockerfile"), "FROM alpine\n").unwrap();
+    std::fs::write(dir.join("docker/notes.txt"), "ignored\n").unwrap();
+    std::fs::write(dir.join("main.rs"), "fn main() {}\n").unwrap();
+
+    let context = build::tar_context(&dir, "docker/Dockerfile").unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+    let mut archive = tar::Archive::new(&context[..]);
+    let paths: Vec<String> = archive
+        .entries()
+        .unwrap()
+        .map(|entry| entry.unwrap().path().unwrap().display().to_string())
+        .collect();
+    assert_eq!(paths, vec![".dockerignore", "docker/Dockerfile", "main.rs"]);
+}
+
+#[test]
+#[cfg(test)]
+fn search_images() {
+    let response = search_images_response();
+    let _: Vec<ImageSearchResult> = match serde_json::from_str(&response) {
+        Ok(body) => body,
+        Err(e) => panic!("{}", e),
+    };
+
+    let (socket, daemon) = fake_daemon("search_images", "200 OK", response);
+    let mut docker = Docker::connect_to(socket.to_str().unwrap()).unwrap();
+    let filters = ImageSearchFilters {
+        is_official: Some(true),
+        ..Default::default()
+    };
+    let results = docker.search_images("alpine", Some(1), &filters).unwrap();
+    assert_eq!(results[0].name, "alpine");
+    assert_eq!(
+        daemon.join().unwrap().0,
+        "GET /images/search?term=alpine&limit=1&filters=%7B%22is-official%22%3A%5B%22true%22%5D%7D HTTP/1.1"
+    );
+}
+
+#[test]
+#[cfg(test)]
+fn get_volumes() {
+    let response = get_volumes_response();
+    let _: VolumeList = match serde_json::from_str(&response) {
+        Ok(body) => body,
+        Err(e) => panic!("{}", e),
+    };
+
+    let (socket, daemon) = fake_daemon("get_volumes", "200 OK", response);
+    let mut docker = Docker::connect_to(socket.to_str().unwrap()).unwrap();
+    let options = VolumeListOptions {
+        dangling: Some(false),
+        ..Default::default()
+    };
+    let list = docker.get_volumes(&options).unwrap();
+    let volumes = list.Volumes.unwrap();
+    assert_eq!(
+        volumes[0].Mountpoint,
+        "/var/lib/docker/volumes/pgdata/_data"
+    );
+    assert_eq!(
+        volumes[0].Labels.as_ref().unwrap()["com.example.backup"],
+        "daily"
+    );
+    assert_eq!(list.Warnings.unwrap().len(), 1);
+    assert_eq!(
+        daemon.join().unwrap().0,
+        "GET /volumes?filters=%7B%22dangling%22%3A%5B%22false%22%5D%7D HTTP/1.1"
+    );
+}
+
+#[test]
+#[cfg(test)]
+fn volumes() {
+    let volume = "{\"CreatedAt\":\"2021-06-22T09:21:47Z\",\"Driver\":\"local\",\"Labels\":{\"com.example.backup\":\"daily\"},\"Mountpoint\":\"/var/lib/docker/volumes/pgdata/_data\",\"Name\":\"pgdata\",\"Options\":{\"type\":\"tmpfs\"},\"Scope\":\"local\"}".to_string();
+    let (socket, daemon) = fake_daemon_sequence(
+        "volumes",
+        vec![
+            ("200 OK", volume.clone()),
+            ("201 Created", volume),
+            ("204 No Content", String::new()),
+            (
+                "200 OK",
+                "{\"VolumesDeleted\":[\"pgdata\"],\"SpaceReclaimed\":7408}".to_string(),
+            ),
+        ],
+    );
+    let mut docker = Docker::connect_to(socket.to_str().unwrap()).unwrap();
+
+    let volume = docker.inspect_volume("pgdata").unwrap();
+    assert_eq!(volume.Mountpoint, "/var/lib/docker/volumes/pgdata/_data");
+    assert_eq!(volume.Options.unwrap()["type"], "tmpfs");
+
+    let mut labels = HashMap::new();
+    labels.insert("com.example.backup".to_string(), "daily".to_string());
+    let mut options = HashMap::new();
+    options.insert("type".to_string(), "tmpfs".to_string());
+    let created = docker
+        .create_volume(VolumeCreate {
+            Name: Some("pgdata".to_string()),
+            Driver: Some("local".to_string()),
+            DriverOpts: Some(options),
+            Labels: Some(labels),
+        })
+        .unwrap();
+    assert_eq!(created.Name, "pgdata");
+
+    docker.remove_volume("pgdata", true).unwrap();
+
+    let prune = docker
+        .prune_volumes(&VolumePruneFilters {
+            label: vec!["com.example.backup=daily".to_string()],
+            ..Default::default()
+        })
+        .unwrap();
+    assert_eq!(prune.VolumesDeleted.unwrap(), vec!["pgdata".to_string()]);
+    assert_eq!(prune.SpaceReclaimed, 7408);
+
+    let requests = daemon.join().unwrap();
+    assert_eq!(requests[0].0, "GET /volumes/pgdata HTTP/1.1");
+    assert_eq!(requests[1].0, "POST /volumes/create HTTP/1.1");
+    let body: serde_json::Value = serde_json::from_str(&requests[1].1).unwrap();
+    assert_eq!(
+        body,
+        serde_json::json!({
+            "Name": "pgdata",
+            "Driver": "local",
+            "DriverOpts": {"type": "tmpfs"},
+            "Labels": {"com.example.backup": "daily"},
+        })
+    );
+    assert_eq!(requests[2].0, "DELETE /volumes/pgdata?force=true HTTP/1.1");
+    assert_eq!(
+        requests[3].0,
+        "POST /volumes/prune?filters=%7B%22label%22%3A%5B%22com.example.backup%3Ddaily%22%5D%7D HTTP/1.1"
+    );
+}
+
+#[test]
+#[cfg(test)]
+fn raw_request() {
+    let (socket, daemon) = fake_daemon(
+        "raw_request",
+        "200 OK",
+        "{\"Id\":\"wd3ol6pfcbzh8xi1ttn4xj1ro\"}".to_string(),
+    );
+    let mut docker = Docker::connect_to(socket.to_str().unwrap())
+        .unwrap()
+        .with_api_version("v1.41");
+    let res = docker
+        .raw_post(
+            "/configs/create",
+            &[("label", "a b")],
+            RawBody::from("{\"Name\":\"server.conf\"}"),
+        )
+        .unwrap();
+    assert_eq!(res.status, crate::StatusCode::OK);
+    assert_eq!(res.header("content-type"), Some("application/json"));
+    let created: serde_json::Value = res.json().unwrap();
+    assert_eq!(created["Id"], "wd3ol6pfcbzh8xi1ttn4xj1ro");
+    let (request_line, body) = daemon.join().unwrap();
+    assert_eq!(
+        request_line,
+        "POST /v1.41/configs/create?label=a%20b HTTP/1.1"
+    );
+    assert_eq!(body, "{\"Name\":\"server.conf\"}");
+
+    let (socket, daemon) = fake_daemon(
+        "raw_request_error",
+        "500 Internal Server Error",
+        "{\"message\":\"This node is not a swarm manager.\"}".to_string(),
+    );
+    let mut docker = Docker::connect_to(socket.to_str().unwrap()).unwrap();
+    let e = docker.raw_get("/configs", &[]).err().unwrap();
+    daemon.join().unwrap();
+    assert_eq!(
+        e.to_string(),
+        "Server error:Internal Server Error: This node is not a swarm manager."
+    );
+}
+
+#[test]
+#[cfg(test)]
+fn request_timeout() {
+    let (socket, _listener) = silent_daemon("request_timeout");
+    let mut docker = Docker::connect_to(socket.to_str().unwrap())
+        .unwrap()
+        .with_timeout(Duration::from_millis(200));
+    let start = Instant::now();
+    let e = docker.get_version().unwrap_err();
+    assert_eq!(e.kind(), std::io::ErrorKind::TimedOut);
+    assert!(start.elapsed() < Duration::from_secs(5));
+
+    let mut docker = Docker::connect_to(socket.to_str().unwrap()).unwrap();
+    let e = docker
+        .timeout(Duration::from_millis(200))
+        .ping()
+        .unwrap_err();
+    assert_eq!(e.kind(), std::io::ErrorKind::TimedOut);
+
+    let e = docker
+        .timeout(Duration::from_millis(200))
+        .stream_events(None)
+        .err()
+        .unwrap();
+    assert_eq!(e.kind(), std::io::ErrorKind::TimedOut);
+
+    // Streaming calls are bounded by the default timeout while the daemon
+    // does not answer, whatever they send.
+    let mut docker = Docker::connect_to(socket.to_str().unwrap())
+        .unwrap()
+        .with_connect_timeout(Duration::from_millis(200))
+        .with_timeout(Duration::from_millis(200));
+    let start = Instant::now();
+    let e = docker.save_images(&["alpine:3.14"]).err().unwrap();
+    assert_eq!(e.kind(), std::io::ErrorKind::TimedOut);
+    let context = std::io::Cursor::new(b"not really a tar archive".to_vec());
+    let e = docker
+        .build_image(context, &Default::default())
+        .err()
+        .unwrap();
+    assert_eq!(e.kind(), std::io::ErrorKind::TimedOut);
+    assert!(start.elapsed() < Duration::from_secs(5));
+}
+
+#[test]
+#[cfg(test)]
+fn streaming_timeout() {
+    // The daemon answers at once but takes longer than the timeout to send
+    // the body, which must not be cut.
+    let (socket, listener) = silent_daemon("streaming_timeout");
+    let daemon = std::thread::spawn(move || {
+        for _ in 0..2 {
+            let (mut conn, _, _) = accept_request(&listener);
+            write!(
+                conn.get_mut(),
+                "HTTP/1.1 200 OK\r\nContent-Type: application/x-tar\r\nContent-Length: 6\r\nConnection: close\r\n\r\nslow"
+            )
+            .unwrap();
+            std::thread::sleep(Duration::from_millis(400));
+            write!(conn.get_mut(), "ly").unwrap();
+        }
+    });
+
+    let mut docker = Docker::connect_to(socket.to_str().unwrap())
+        .unwrap()
+        .with_timeout(Duration::from_millis(200));
+    let mut exported = String::new();
+    docker
+        .export_container("web")
+        .unwrap()
+        .read_to_string(&mut exported)
+        .unwrap();
+    assert_eq!(exported, "slowly");
+
+    let mut saved = String::new();
+    docker
+        .timeout(Duration::from_millis(200))
+        .save_images(&["alpine:3.14"])
+        .unwrap()
+        .read_to_string(&mut saved)
+        .unwrap();
+    assert_eq!(saved, "slowly");
+    daemon.join().unwrap();
+}
+
+#[test]
+#[cfg(test)]
+fn stream_events_cancel() {
+    let socket = std::env::temp_dir().join(format!(
+        "docker-sync-stream_events_cancel-{}.sock",
+        std::process::id()
+    ));
+    let _ = std::fs::remove_file(&socket);
+    let listener = UnixListener::bind(&socket).unwrap();
+    let daemon = std::thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let event = stream_events_response();
+        write!(
+            stream,
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nTransfer-Encoding: chunked\r\n\r\n{:x}\r\n{}\r\n",
+            event.len(),
+            event
+        )
+        .unwrap();
+        // Hold the connection open without sending anything else.
+        let mut buf = [0; 1024];
+        while stream.read(&mut buf).unwrap_or(0) > 0 {}
+    });
+
+    let mut docker = Docker::connect_to(socket.to_str().unwrap()).unwrap();
+    let mut events = docker.stream_events(None).unwrap();
+    let event: Event = events.next().unwrap().unwrap();
+    assert_eq!(event.Action, "health_status: healthy");
+
+    let handle = events.cancel_handle();
+    let canceller = std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_millis(100));
+        handle.cancel();
+    });
+    assert!(events.next().is_none());
+    canceller.join().unwrap();
+    daemon.join().unwrap();
+}
+
+#[test]
+#[cfg(test)]
+fn retry_policy() {
+    let unavailable = "{\"message\":\"daemon is restarting\"}".to_string();
+    let (socket, daemon) = fake_daemon_sequence(
+        "retry_policy",
+        vec![
+            ("503 Service Unavailable", unavailable.clone()),
+            ("503 Service Unavailable", unavailable.clone()),
+            ("200 OK", get_version_response()),
+            ("503 Service Unavailable", unavailable.clone()),
+        ],
+    );
+    let retries = Arc::new(AtomicU32::new(0));
+    let counter = retries.clone();
+    let policy = RetryPolicy {
+        initial_backoff: Duration::from_millis(10),
+        ..Default::default()
+    }
+    .on_retry(move |retry| {
+        assert_eq!(retry.url, "/version");
+        counter.fetch_add(1, Ordering::SeqCst);
+    });
+    let mut docker = Docker::connect_to(socket.to_str().unwrap())
+        .unwrap()
+        .with_retry_policy(policy);
+
+    let version = docker.get_version().unwrap();
+    assert_eq!(version.Version, "1.8.1");
+    assert_eq!(retries.load(Ordering::SeqCst), 2);
+
+    // Writes are never retried.
+    let e = docker.delete_network("isolated_nw").unwrap_err();
+    assert!(ApiError::from_io(&e).unwrap().status.is_server_error());
+    assert_eq!(retries.load(Ordering::SeqCst), 2);
+    assert_eq!(daemon.join().unwrap().len(), 4);
+
+    // A body cut short is retried too.
+    let (socket, listener) = silent_daemon("retry_policy_truncated");
+    let daemon = std::thread::spawn(move || {
+        let (mut conn, _, _) = accept_request(&listener);
+        write!(
+            conn.get_mut(),
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: 200\r\nConnection: close\r\n\r\n{{\"Vers"
+        )
+        .unwrap();
+        drop(conn);
+        let body = get_version_response();
+        let (mut conn, _, _) = accept_request(&listener);
+        write!(
+            conn.get_mut(),
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            body.len(),
+            body
+        )
+        .unwrap();
+    });
+    let errors = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let seen = errors.clone();
+    let policy = RetryPolicy {
+        initial_backoff: Duration::from_millis(10),
+        ..Default::default()
+    }
+    .on_retry(move |retry| seen.lock().unwrap().push(retry.error.kind()));
+    let mut docker = Docker::connect_to(socket.to_str().unwrap())
+        .unwrap()
+        .with_retry_policy(policy);
+    assert_eq!(docker.get_version().unwrap().Version, "1.8.1");
+    assert_eq!(
+        *errors.lock().unwrap(),
+        vec![std::io::ErrorKind::UnexpectedEof]
+    );
+    daemon.join().unwrap();
+}
+
+/// Accepts connections on a fresh unix socket but never answers them.
+#[cfg(test)]
+fn silent_daemon(name: &str) -> (PathBuf, UnixListener) {
+    let socket =
+        std::env::temp_dir().join(format!("docker-sync-{}-{}.sock", name, std::process::id()));
+    let _ = std::fs::remove_file(&socket);
+    let listener = UnixListener::bind(&socket).unwrap();
+    (socket, listener)
+}
+
+/// Accepts a connection and reads a request off it, returning the connection
+/// along with the request line and body.
+#[cfg(test)]
+fn accept_request(listener: &UnixListener) -> (BufReader<UnixStream>, String, String) {
+    let (stream, _) = listener.accept().unwrap();
+    let mut reader = BufReader::new(stream);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).unwrap();
+    let mut length = 0;
+    let mut line = String::new();
+    while reader.read_line(&mut line).unwrap() > 2 {
+        let lower = line.to_lowercase();
+        if let Some(value) = lower.strip_prefix("content-length:") {
+            length = value.trim().parse().unwrap();
+        }
+        line.clear();
+    }
+    let mut request_body = vec![0; length];
+    reader.read_exact(&mut request_body).unwrap();
+    (
+        reader,
+        request_line.trim_end().to_string(),
+        String::from_utf8(request_body).unwrap(),
+    )
+}
+
+/// Serves a single canned response on a fresh unix socket, and returns the
+/// request line and body it received once the exchange is over.
+#[cfg(test)]
+fn fake_daemon(name: &str, status: &str, body: String) -> (PathBuf, JoinHandle<(String, String)>) {
+    let (socket, daemon) = fake_daemon_sequence(name, vec![(status, body)]);
+    (
+        socket,
+        std::thread::spawn(move || daemon.join().unwrap().remove(0)),
+    )
+}
+
+/// Serves one canned response per connection, in order, and returns the
+/// request lines and bodies it received.
+#[cfg(test)]
+fn fake_daemon_sequence(
+    name: &str,
+    responses: Vec<(&str, String)>,
+) -> (PathBuf, JoinHandle<Vec<(String, String)>>) {
+    let socket =
+        std::env::temp_dir().join(format!("docker-sync-{}-{}.sock", name, std::process::id()));
+    let _ = std::fs::remove_file(&socket);
+    let listener = UnixListener::bind(&socket).unwrap();
+    let responses: Vec<(String, String)> = responses
+        .into_iter()
+        .map(|(status, body)| (status.to_string(), body))
+        .collect();
+
+    let daemon = std::thread::spawn(move || {
+        let mut requests = Vec::new();
+        for (status, body) in responses {
+            let (mut reader, request_line, request_body) = accept_request(&listener);
+            write!(
+                reader.get_mut(),
+                "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                body.len(),
+                body
+            )
+            .unwrap();
+            requests.push((request_line, request_body));
+        }
+        requests
+    });
+
+    (socket, daemon)
+}
+
 #[cfg(test)]
 fn get_networks_response() -> String {
     return "[{\"Name\":\"bridge\",\"Id\":\"f2de39df4171b0dc801e8002d1d999b77256983dfc63041c0f34030aa3977566\",\"Created\":\"2016-10-19T06:21:00.416543526Z\",\"Scope\":\"local\",\"Driver\":\"bridge\",\"EnableIPv6\":false,\"Internal\":false,\"Attachable\":false,\"Ingress\":false,\"IPAM\":{\"Driver\":\"default\",\"Config\":[{\"Subnet\":\"172.17.0.0/16\"}]},\"Options\":{\"com.docker.network.bridge.default_bridge\":\"true\",\"com.docker.network.bridge.enable_icc\":\"true\",\"com.docker.network.bridge.enable_ip_masquerade\":\"true\",\"com.docker.network.bridge.host_binding_ipv4\":\"0.0.0.0\",\"com.docker.network.bridge.name\":\"docker0\",\"com.docker.network.driver.mtu\":\"1500\"}},{\"Name\":\"none\",\"Id\":\"e086a3893b05ab69242d3c44e49483a3bbbd3a26b46baa8f61ab797c1088d794\",\"Created\":\"0001-01-01T00:00:00Z\",\"Scope\":\"local\",\"Driver\":null,\"EnableIPv6\":false,\"Internal\":false,\"Attachable\":false,\"Ingress\":false,\"IPAM\":{\"Driver\":\"default\",\"Config\":[]},\"Containers\":{},\"Options\":{}},{\"Name\":\"host\",\"Id\":\"13e871235c677f196c4e1ecebb9dc733b9b2d2ab589e30c539efeda84a24215e\",\"Created\":\"0001-01-01T00:00:00Z\",\"Scope\":\"local\",\"Driver\":\"host\",\"EnableIPv6\":false,\"Internal\":false,\"Attachable\":false,\"Ingress\":false,\"IPAM\":{\"Driver\":\"default\",\"Config\":[]},\"Containers\":{},\"Options\":{},\"Labels\":{}}]".to_string();
@@ -164,7 +2008,8 @@ fn get_container_info_response() -> String {
 
 #[cfg(test)]
 fn get_processes_response() -> String {
-    return "{\"Processes\":[[\"4586\",\"999\",\"rust\"]],\"Titles\":[\"PID\",\"USER\",\"COMMAND\"]}".to_string();
+    return "{\"Processes\":[[\"4586\",\"999\",\"rust\"]],\"Titles\":[\"PID\",\"USER\",\"COMMAND\"]}"
+        .to_string();
 }
 
 #[cfg(test)]
@@ -176,3 +2021,117 @@ fn get_filesystem_changes_response() -> String {
 fn get_version_response() -> String {
     return "{\"Version\":\"1.8.1\",\"ApiVersion\":\"1.20\",\"GitCommit\":\"d12ea79\",\"GoVersion\":\"go1.4.2\",\"Os\":\"linux\",\"Arch\":\"amd64\",\"KernelVersion\":\"4.0.9-boot2docker\",\"BuildTime\":\"Thu Aug 13 02:49:29 UTC 2015\"}".to_string();
 }
+
+#[cfg(test)]
+fn load_image_response() -> String {
+    "{\"status\":\"Loading layer\",\"progressDetail\":{\"current\":32768,\"total\":5820416},\"progress\":\"[>                                                  ]  32.77kB/5.82MB\",\"id\":\"8d3ac3489996\"}\r\n{\"status\":\"Loading layer\",\"progressDetail\":{\"current\":5820416,\"total\":5820416},\"progress\":\"[==================================================\\u003e]  5.82MB/5.82MB\",\"id\":\"8d3ac3489996\"}\r\n{\"stream\":\"Loaded image: alpine:3.14\\n\"}\r\n".to_string()
+}
+
+#[cfg(test)]
+fn build_image_response() -> String {
+    "{\"stream\":\"Step 1/2 : FROM alpine:3.14\"}\r\n{\"status\":\"Pulling fs layer\",\"progressDetail\":{},\"id\":\"8d3ac3489996\"}\r\n{\"stream\":\"Step 2/2 : RUN false\"}\r\n{\"aux\":{\"ID\":\"sha256:1fd3d6a3e7a5b5e0e4c2b55d8f8c4a2a6bd5ff7f2e7c2f5fe0b2a4d3e1d1c0f9\"}}\r\n{\"errorDetail\":{\"code\":1,\"message\":\"The command '/bin/sh -c false' returned a non-zero code: 1\"},\"error\":\"The command '/bin/sh -c false' returned a non-zero code: 1\"}\r\n{\"aux\":{\"Tag\":\"3.14\",\"Digest\":\"sha256:e2e16842c9b54d985bf1ef9242a313f36b856181f188de21313820e177002501\",\"Size\":528}}".to_string()
+}
+
+#[cfg(test)]
+fn search_images_response() -> String {
+    "[{\"description\":\"A minimal Docker image based on Alpine Linux with a complete package index and only 5 MB in size!\",\"is_automated\":false,\"is_official\":true,\"name\":\"alpine\",\"star_count\":10093}]".to_string()
+}
+
+#[cfg(test)]
+fn inspect_network_response() -> String {
+    "{\"Name\":\"overlay-net\",\"Id\":\"7d86d31b1478e7cca9ebed7e73aa0fdeec46c5ca29497431d3007d2d9e15ed99\",\"Created\":\"2021-06-23T14:38:12.123456789Z\",\"Scope\":\"swarm\",\"Driver\":\"overlay\",\"EnableIPv6\":false,\"IPAM\":{\"Driver\":\"default\",\"Options\":null,\"Config\":[{\"Subnet\":\"10.0.0.0/24\",\"Gateway\":\"10.0.0.1\"}]},\"Internal\":false,\"Attachable\":true,\"Ingress\":false,\"ConfigFrom\":{\"Network\":\"\"},\"ConfigOnly\":false,\"Containers\":{\"19a4d5d687db25203351ed79d478946f861258f018fe384f229f2efa4b23513c\":{\"Name\":\"web.1.qz5mj2ky2cyuvdnoff1qovl0u\",\"EndpointID\":\"628cadb8bcb92de107b2a1e516cbffe463e321f548feb37697cce00ad694f21a\",\"MacAddress\":\"02:42:0a:00:00:05\",\"IPv4Address\":\"10.0.0.5/24\",\"IPv6Address\":\"\"}},\"Options\":{\"com.docker.network.driver.overlay.vxlanid_list\":\"4097\"},\"Labels\":{},\"Peers\":[{\"Name\":\"6a8b8e1c3d44\",\"IP\":\"192.168.1.10\"}],\"Services\":{\"web\":{\"VIP\":\"10.0.0.2\",\"Ports\":[],\"LocalLBIndex\":257,\"Tasks\":[{\"Name\":\"web.1.qz5mj2ky2cyuvdnoff1qovl0u\",\"EndpointID\":\"628cadb8bcb92de107b2a1e516cbffe463e321f548feb37697cce00ad694f21a\",\"EndpointIP\":\"10.0.0.5\",\"Info\":{\"Host IP\":\"192.168.1.10\"}}]}}}".to_string()
+}
+
+#[cfg(test)]
+fn get_volumes_response() -> String {
+    "{\"Volumes\":[{\"CreatedAt\":\"2021-06-22T09:21:47Z\",\"Driver\":\"local\",\"Labels\":{\"com.example.backup\":\"daily\"},\"Mountpoint\":\"/var/lib/docker/volumes/pgdata/_data\",\"Name\":\"pgdata\",\"Options\":null,\"Scope\":\"local\"},{\"CreatedAt\":\"2021-06-20T17:02:11Z\",\"Driver\":\"local\",\"Labels\":null,\"Mountpoint\":\"/var/lib/docker/volumes/3bd6f6b0c4c1b9e1c8f7a6d1e2f3a4b5c6d7e8f9a0b1c2d3e4f5a6b7c8d9e0f1/_data\",\"Name\":\"3bd6f6b0c4c1b9e1c8f7a6d1e2f3a4b5c6d7e8f9a0b1c2d3e4f5a6b7c8d9e0f1\",\"Options\":null,\"Scope\":\"local\"}],\"Warnings\":[\"volume driver nfs-plugin is not responding\"]}".to_string()
+}
+
+#[cfg(test)]
+fn disk_usage_response() -> String {
+    "{\"LayersSize\":1092588,\"Images\":[{\"Id\":\"sha256:2b8fd9751c4c0f5dd266fcae00707e67a2545ef34f9a29354585f93dac906749\",\"ParentId\":\"\",\"RepoTags\":[\"busybox:latest\"],\"RepoDigests\":[\"busybox@sha256:a59906e33509d14c036c8678d687bd4eec81ed7c4b8ce907b888c607f6a1e0e6\"],\"Created\":1466724217,\"Size\":1092588,\"SharedSize\":0,\"VirtualSize\":1092588,\"Labels\":{},\"Containers\":1},{\"Id\":\"sha256:3f5ef9003cefbc9dbb5a0d3a5d1bb9a1b7e0b1e1a3f6c4e1d3b2a1f0e9d8c7b6\",\"ParentId\":\"\",\"RepoTags\":null,\"RepoDigests\":null,\"Created\":1466724100,\"Size\":-1,\"SharedSize\":-1,\"Labels\":null,\"Containers\":0}],\"Containers\":[{\"Id\":\"e575172ed11dc01bfce087fb27bee502db149e1a0fad7c296ad300bbff178148\",\"Names\":[\"/top\"],\"Image\":\"busybox\",\"ImageID\":\"sha256:2b8fd9751c4c0f5dd266fcae00707e67a2545ef34f9a29354585f93dac906749\",\"Command\":\"top\",\"Created\":1472592424,\"Ports\":[],\"SizeRootFs\":1092588,\"SizeRw\":12,\"Labels\":{},\"State\":\"exited\",\"Status\":\"Exited (0) 56 minutes ago\",\"HostConfig\":{\"NetworkMode\":\"default\"},\"NetworkSettings\":{\"Networks\":{}},\"Mounts\":[]},{\"Id\":\"b2d9c1e0f7a6\",\"Names\":[\"/paused\"],\"Image\":\"busybox\",\"ImageID\":\"sha256:2b8fd9751c4c0f5dd266fcae00707e67a2545ef34f9a29354585f93dac906749\",\"Command\":\"sleep 1d\",\"Created\":1472592500,\"Ports\":[],\"SizeRootFs\":1092688,\"SizeRw\":100,\"Labels\":{},\"State\":\"paused\",\"Status\":\"Up 50 minutes (Paused)\",\"HostConfig\":{\"NetworkMode\":\"default\"},\"NetworkSettings\":{\"Networks\":{}},\"Mounts\":[]}],\"Volumes\":[{\"Name\":\"my-volume\",\"Driver\":\"local\",\"Mountpoint\":\"/var/lib/docker/volumes/my-volume/_data\",\"Labels\":null,\"Scope\":\"local\",\"Options\":null,\"UsageData\":{\"Size\":1024,\"RefCount\":0}},{\"Name\":\"pgdata\",\"Driver\":\"local\",\"Mountpoint\":\"/var/lib/docker/volumes/pgdata/_data\",\"Labels\":null,\"Scope\":\"local\",\"Options\":null,\"UsageData\":{\"Size\":7408,\"RefCount\":1}}],\"BuildCache\":[{\"ID\":\"hw53o5aio51xtltp5xjp8v7fx\",\"Parents\":[],\"Type\":\"regular\",\"Description\":\"pulled from docker.io/library/debian@sha256:234cb88d3020898631af0ccbbcca9a66ae7306ecd30c9720690858c1b007d2a0\",\"InUse\":false,\"Shared\":true,\"Size\":0,\"CreatedAt\":\"2021-06-28T13:31:01.474619385Z\",\"LastUsedAt\":\"2021-07-07T22:02:32.738075951Z\",\"UsageCount\":26},{\"ID\":\"ndlpt0hhvkqcdfkputsk4cq9c\",\"Parents\":[\"ndlpt0hhvkqcdfkputsk4cq9c\"],\"Type\":\"regular\",\"Description\":\"mount / from exec /bin/sh -c echo 'Binary::apt::APT::Keep-Downloaded-Packages \\\"true\\\";' > /etc/apt/apt.conf.d/keep-cache\",\"InUse\":false,\"Shared\":false,\"Size\":51,\"CreatedAt\":\"2021-06-28T13:31:03.002625487Z\",\"LastUsedAt\":\"2021-07-07T22:02:32.773909517Z\",\"UsageCount\":26}]}".to_string()
+}
+
+#[cfg(test)]
+fn get_system_info_20_10_response() -> String {
+    "{\"ID\":\"7TRN:IPZB:QYBB:VPBQ:UWVM:2RJJ:5RDR:KFYN:BAIW:4VJ5:WKM3:GJ2X\",\"Containers\":14,\"ContainersRunning\":3,\"ContainersPaused\":1,\"ContainersStopped\":10,\"Images\":508,\"Driver\":\"overlay2\",\"DriverStatus\":[[\"Backing Filesystem\",\"extfs\"],[\"Supports d_type\",\"true\"],[\"Native Overlay Diff\",\"true\"],[\"userxattr\",\"false\"]],\"Plugins\":{\"Volume\":[\"local\"],\"Network\":[\"bridge\",\"host\",\"ipvlan\",\"macvlan\",\"null\",\"overlay\"],\"Authorization\":null,\"Log\":[\"awslogs\",\"fluentd\",\"gcplogs\",\"gelf\",\"journald\",\"json-file\",\"local\",\"logentries\",\"splunk\",\"syslog\"]},\"MemoryLimit\":true,\"SwapLimit\":false,\"KernelMemory\":true,\"KernelMemoryTCP\":true,\"CpuCfsPeriod\":true,\"CpuCfsQuota\":true,\"CPUShares\":true,\"CPUSet\":true,\"PidsLimit\":true,\"IPv4Forwarding\":true,\"BridgeNfIptables\":true,\"BridgeNfIp6tables\":true,\"Debug\":false,\"NFd\":42,\"OomKillDisable\":true,\"NGoroutines\":55,\"SystemTime\":\"2021-06-23T16:53:22.214917164+02:00\",\"LoggingDriver\":\"json-file\",\"CgroupDriver\":\"cgroupfs\",\"CgroupVersion\":\"1\",\"NEventsListener\":0,\"KernelVersion\":\"5.10.0-7-amd64\",\"OperatingSystem\":\"Debian GNU/Linux 11 (bullseye)\",\"OSVersion\":\"11\",\"OSType\":\"linux\",\"Architecture\":\"x86_64\",\"IndexServerAddress\":\"https://index.docker.io/v1/\",\"RegistryConfig\":{\"AllowNondistributableArtifactsCIDRs\":[],\"AllowNondistributableArtifactsHostnames\":[],\"InsecureRegistryCIDRs\":[\"127.0.0.0/8\"],\"IndexConfigs\":{\"docker.io\":{\"Name\":\"docker.io\",\"Mirrors\":[],\"Secure\":true,\"Official\":true}},\"Mirrors\":[]},\"NCPU\":8,\"MemTotal\":16624214016,\"GenericResources\":null,\"DockerRootDir\":\"/var/lib/docker\",\"HttpProxy\":\"\",\"HttpsProxy\":\"\",\"NoProxy\":\"\",\"Name\":\"hubblo-dev\",\"Labels\":[],\"ExperimentalBuild\":false,\"ServerVersion\":\"20.10.7\",\"Runtimes\":{\"io.containerd.runc.v2\":{\"path\":\"runc\"},\"io.containerd.runtime.v1.linux\":{\"path\":\"runc\"},\"runc\":{\"path\":\"runc\"}},\"DefaultRuntime\":\"runc\",\"Swarm\":{\"NodeID\":\"\",\"NodeAddr\":\"\",\"LocalNodeState\":\"inactive\",\"ControlAvailable\":false,\"Error\":\"\",\"RemoteManagers\":null},\"LiveRestoreEnabled\":false,\"Isolation\":\"\",\"InitBinary\":\"docker-init\",\"ContainerdCommit\":{\"ID\":\"d71fcd7d8303cbf684402823e425e9dd2e99285d\",\"Expected\":\"d71fcd7d8303cbf684402823e425e9dd2e99285d\"},\"RuncCommit\":{\"ID\":\"b9ee9c6314599f1b4a7f497e1f1f856fe433d3b7\",\"Expected\":\"b9ee9c6314599f1b4a7f497e1f1f856fe433d3b7\"},\"InitCommit\":{\"ID\":\"de40ad0\",\"Expected\":\"de40ad0\"},\"SecurityOptions\":[\"name=apparmor\",\"name=seccomp,profile=default\"],\"Warnings\":[\"WARNING: No swap limit support\"]}".to_string()
+}
+
+#[cfg(test)]
+fn get_system_info_24_0_response() -> String {
+    "{\"ID\":\"e1b5a6f2-3c4d-4e5f-8a9b-0c1d2e3f4a5b\",\"Containers\":2,\"ContainersRunning\":2,\"ContainersPaused\":0,\"ContainersStopped\":0,\"Images\":31,\"Driver\":\"overlay2\",\"DriverStatus\":[[\"Backing Filesystem\",\"btrfs\"],[\"Supports d_type\",\"true\"],[\"Using metacopy\",\"false\"],[\"Native Overlay Diff\",\"true\"],[\"userxattr\",\"false\"]],\"Plugins\":{\"Volume\":[\"local\"],\"Network\":[\"bridge\",\"host\",\"ipvlan\",\"macvlan\",\"null\",\"overlay\"],\"Authorization\":null,\"Log\":[\"awslogs\",\"fluentd\",\"gcplogs\",\"gelf\",\"journald\",\"json-file\",\"local\",\"splunk\",\"syslog\"]},\"MemoryLimit\":true,\"SwapLimit\":true,\"CpuCfsPeriod\":true,\"CpuCfsQuota\":true,\"CPUShares\":true,\"CPUSet\":true,\"PidsLimit\":true,\"IPv4Forwarding\":true,\"BridgeNfIptables\":true,\"BridgeNfIp6tables\":true,\"Debug\":false,\"NFd\":36,\"OomKillDisable\":false,\"NGoroutines\":49,\"SystemTime\":\"2023-09-12T10:04:51.830186217Z\",\"LoggingDriver\":\"json-file\",\"CgroupDriver\":\"systemd\",\"CgroupVersion\":\"2\",\"NEventsListener\":0,\"KernelVersion\":\"6.4.15-200.fc38.x86_64\",\"OperatingSystem\":\"Fedora Linux 38 (Workstation Edition)\",\"OSVersion\":\"38\",\"OSType\":\"linux\",\"Architecture\":\"x86_64\",\"IndexServerAddress\":\"https://index.docker.io/v1/\",\"RegistryConfig\":{\"AllowNondistributableArtifactsCIDRs\":null,\"AllowNondistributableArtifactsHostnames\":null,\"InsecureRegistryCIDRs\":[\"::1/128\",\"127.0.0.0/8\"],\"IndexConfigs\":{\"docker.io\":{\"Name\":\"docker.io\",\"Mirrors\":[],\"Secure\":true,\"Official\":true}},\"Mirrors\":null},\"NCPU\":16,\"MemTotal\":33325735936,\"GenericResources\":null,\"DockerRootDir\":\"/var/lib/docker\",\"HttpProxy\":\"\",\"HttpsProxy\":\"\",\"NoProxy\":\"\",\"Name\":\"workstation\",\"Labels\":[],\"ExperimentalBuild\":false,\"ServerVersion\":\"24.0.6\",\"Runtimes\":{\"io.containerd.runc.v2\":{\"path\":\"runc\"},\"runc\":{\"path\":\"runc\"}},\"DefaultRuntime\":\"runc\",\"Swarm\":{\"NodeID\":\"qgsqsfazvp3ddq7fzwxqcjuq5\",\"NodeAddr\":\"192.168.1.10\",\"LocalNodeState\":\"active\",\"ControlAvailable\":true,\"Error\":\"\",\"RemoteManagers\":[{\"NodeID\":\"qgsqsfazvp3ddq7fzwxqcjuq5\",\"Addr\":\"192.168.1.10:2377\"}],\"Nodes\":1,\"Managers\":1,\"Cluster\":{\"ID\":\"kv4nf3qxl1lrbw0dfe4k7h2p8\",\"Version\":{\"Index\":10},\"CreatedAt\":\"2023-09-12T09:58:13.191947538Z\",\"UpdatedAt\":\"2023-09-12T09:58:13.707410393Z\",\"Spec\":{\"Name\":\"default\"},\"TLSInfo\":{},\"RootRotationInProgress\":false,\"DataPathPort\":4789,\"DefaultAddrPool\":[\"10.0.0.0/8\"],\"SubnetSize\":24}},\"LiveRestoreEnabled\":false,\"Isolation\":\"\",\"InitBinary\":\"docker-init\",\"ContainerdCommit\":{\"ID\":\"61f9fd88f79f081d64d6fa3bb1a0dc71ec870523\",\"Expected\":\"61f9fd88f79f081d64d6fa3bb1a0dc71ec870523\"},\"RuncCommit\":{\"ID\":\"v1.1.9-0-gccaecfc\",\"Expected\":\"v1.1.9-0-gccaecfc\"},\"InitCommit\":{\"ID\":\"de40ad0\",\"Expected\":\"de40ad0\"},\"SecurityOptions\":[\"name=seccomp,profile=builtin\",\"name=selinux\",\"name=cgroupns\"],\"CDISpecDirs\":[],\"Warnings\":null}".to_string()
+}
+
+#[cfg(test)]
+fn get_events_response() -> String {
+    "{\"status\":\"start\",\"id\":\"3ae23f4a8b1c\",\"from\":\"postgres:13\",\"Type\":\"container\",\"Action\":\"start\",\"Actor\":{\"ID\":\"3ae23f4a8b1c\",\"Attributes\":{\"image\":\"postgres:13\",\"name\":\"db\"}},\"scope\":\"local\",\"time\":1629574695,\"timeNano\":1629574695515050031}\n{\"Type\":\"network\",\"Action\":\"connect\",\"Actor\":{\"ID\":\"7d86d31b1478\",\"Attributes\":{\"container\":\"3ae23f4a8b1c\",\"name\":\"bridge\",\"type\":\"bridge\"}},\"scope\":\"local\",\"time\":1629574695,\"timeNano\":1629574695498761205}\n".to_string()
+}
+
+#[cfg(test)]
+fn stream_events_response() -> String {
+    "{\"status\":\"health_status: healthy\",\"id\":\"3ae23f4a8b1c\",\"from\":\"postgres:13\",\"Type\":\"container\",\"Action\":\"health_status: healthy\",\"Actor\":{\"ID\":\"3ae23f4a8b1c\",\"Attributes\":{\"image\":\"postgres:13\",\"name\":\"db\"}},\"scope\":\"local\",\"time\":1629574695,\"timeNano\":1629574695515050031}\n".to_string()
+}
+
+#[cfg(test)]
+fn exec_output_response() -> String {
+    "\u{1}\u{0}\u{0}\u{0}\u{0}\u{0}\u{0}\u{a}accepting\n\u{2}\u{0}\u{0}\u{0}\u{0}\u{0}\u{0}\u{c}no response\n".to_string()
+}
+
+#[cfg(test)]
+fn stat_path_response() -> String {
+    "eyJuYW1lIjoibmdpbngiLCJzaXplIjo0MDk2LCJtb2RlIjoyMTQ3NDg0MTQxLCJtdGltZSI6IjIwMjMtMDUtMDFUMTA6MDA6MDBaIiwibGlua1RhcmdldCI6IiJ9".to_string()
+}
+
+#[cfg(test)]
+fn filesystem_diff_response() -> String {
+    "[{\"Path\":\"/var\",\"Kind\":0},{\"Path\":\"/var/log\",\"Kind\":0},{\"Path\":\"/var/log/nginx\",\"Kind\":1},{\"Path\":\"/var/log/nginx.log\",\"Kind\":1},{\"Path\":\"/etc\",\"Kind\":0},{\"Path\":\"/etc/resolv.conf\",\"Kind\":2},{\"Path\":\"/var/lib\",\"Kind\":0},{\"Path\":\"/tmp/x\",\"Kind\":7}]".to_string()
+}
+
+/// Inspect response of a running container whose last `failures` checks
+/// failed.
+#[cfg(test)]
+fn health_response(status: &str, failures: i64) -> String {
+    let log: Vec<String> = (1..=failures)
+        .map(|i| {
+            format!(
+                "{{\"Start\":\"2023-05-01T10:00:0{}Z\",\"End\":\"2023-05-01T10:00:0{}Z\",\"ExitCode\":1,\"Output\":\"check {} failed\\n\"}}",
+                i, i, i
+            )
+        })
+        .collect();
+    let health = format!(
+        "{{\"Status\":\"{}\",\"FailingStreak\":{},\"Log\":[{}]}}",
+        status,
+        failures,
+        log.join(",")
+    );
+    container_info_response(true, false, Some(serde_json::from_str(&health).unwrap()))
+}
+
+/// `get_container_info_response` with its running state, TTY and health
+/// replaced.
+#[cfg(test)]
+fn container_info_response(running: bool, tty: bool, health: Option<serde_json::Value>) -> String {
+    let mut info: serde_json::Value = serde_json::from_str(&get_container_info_response()).unwrap();
+    info["State"]["Running"] = running.into();
+    info["Config"]["Tty"] = tty.into();
+    if let Some(health) = health {
+        info["State"]["Health"] = health;
+    }
+    info.to_string()
+}
+
+#[cfg(test)]
+fn container_state_response() -> String {
+    "{\"Status\":\"running\",\"Running\":true,\"Paused\":false,\"Restarting\":false,\"OOMKilled\":false,\"Dead\":false,\"Pid\":2175,\"ExitCode\":0,\"Error\":\"\",\"StartedAt\":\"2023-05-01T10:00:00.123456789Z\",\"FinishedAt\":\"0001-01-01T00:00:00Z\",\"Health\":{\"Status\":\"unhealthy\",\"FailingStreak\":2,\"Log\":[{\"Start\":\"2023-05-01T10:00:30Z\",\"End\":\"2023-05-01T10:00:30.1Z\",\"ExitCode\":0,\"Output\":\"ok\\n\"},{\"Start\":\"2023-05-01T10:01:00Z\",\"End\":\"2023-05-01T10:01:03Z\",\"ExitCode\":1,\"Output\":\"timed out\\n\"},{\"Start\":\"2023-05-01T10:01:30Z\",\"End\":\"2023-05-01T10:01:30.2Z\",\"ExitCode\":1,\"Output\":\"connection refused\\n\"}]}}".to_string()
+}
+
+#[cfg(test)]
+fn get_containers_24_0_response() -> String {
+    "[{\"Id\":\"8dfafdbc3a40a1d2b5f1c0e7e4b8f4e2d0c9b8a7f6e5d4c3b2a1f0e9d8c7b6a5\",\"Names\":[\"/web\"],\"Image\":\"nginx:1.25\",\"ImageID\":\"sha256:a6bd71f48f6839d9faae1f29d3babef831e76bc213107682c5cc80f0cbb30866\",\"Command\":\"/docker-entrypoint.sh nginx -g 'daemon off;'\",\"Created\":1682935200,\"Ports\":[{\"IP\":\"0.0.0.0\",\"PrivatePort\":80,\"PublicPort\":8080,\"Type\":\"tcp\"},{\"IP\":\"::\",\"PrivatePort\":80,\"PublicPort\":8080,\"Type\":\"tcp\"}],\"Labels\":{\"maintainer\":\"NGINX Docker Maintainers <docker-maint@nginx.com>\"},\"State\":\"running\",\"Status\":\"Up 3 minutes (healthy)\",\"HostConfig\":{\"NetworkMode\":\"default\"},\"NetworkSettings\":{\"Networks\":{\"bridge\":{\"IPAMConfig\":null,\"Links\":null,\"Aliases\":null,\"NetworkID\":\"f2de39df4171b0dc801e8002d1d999b77256983dfc63041c0f34030aa3977566\",\"EndpointID\":\"2cdc4edb1ded3631c81f57966563e5c8525b81121bb3706a9a9a3ae102711f3f\",\"Gateway\":\"172.17.0.1\",\"IPAddress\":\"172.17.0.2\",\"IPPrefixLen\":16,\"IPv6Gateway\":\"\",\"GlobalIPv6Address\":\"\",\"GlobalIPv6PrefixLen\":0,\"MacAddress\":\"02:42:ac:11:00:02\",\"DriverOpts\":null}}},\"Mounts\":[{\"Type\":\"volume\",\"Name\":\"webdata\",\"Source\":\"/var/lib/docker/volumes/webdata/_data\",\"Destination\":\"/usr/share/nginx/html\",\"Driver\":\"local\",\"Mode\":\"ro\",\"RW\":false,\"Propagation\":\"\"}]},{\"Id\":\"1c6594faf5276b3f4e8d1a2c9b7e0f3d6a5c8b1e4f7a0d3c6b9e2f5a8d1c4b7e\",\"Names\":[\"/worker\"],\"Image\":\"busybox\",\"ImageID\":\"sha256:a416a98b71e224a31ee99cff8e16063554498227d2b696152a9c3e0aa65e5824\",\"Command\":\"sleep 1d\",\"Created\":1682935100,\"Ports\":[],\"Labels\":{},\"State\":\"paused\",\"Status\":\"Up 5 minutes (Paused)\",\"HostConfig\":{\"NetworkMode\":\"none\"},\"NetworkSettings\":{\"Networks\":{\"none\":{\"IPAMConfig\":null,\"Links\":null,\"Aliases\":null,\"NetworkID\":\"e086a3893b05ab69242d3c44e49483a3bbbd3a26b46baa8f61ab797c1088d794\",\"EndpointID\":\"6f1f3b3c9a2e4d5b8c7a6f5e4d3c2b1a0f9e8d7c6b5a4f3e2d1c0b9a8f7e6d5c\",\"Gateway\":\"\",\"IPAddress\":\"\",\"IPPrefixLen\":0,\"IPv6Gateway\":\"\",\"GlobalIPv6Address\":\"\",\"GlobalIPv6PrefixLen\":0,\"MacAddress\":\"\",\"DriverOpts\":null}}},\"Mounts\":[]}]".to_string()
+}
+
+#[cfg(test)]
+fn list_containers_response() -> String {
+    "[{\"Id\":\"4f2d7c9e8b1a6d3f5e0c2b4a6d8f1e3c5a7b9d0f2e4c6a8b1d3f5e7a9c0b2d4f\",\"Names\":[\"/api\"],\"Image\":\"api:latest\",\"ImageID\":\"sha256:9e2f1c0b7a6d5e4f3c2b1a0d9e8f7c6b5a4d3e2f1c0b9a8d7e6f5c4b3a2d1e0f\",\"Command\":\"./serve\",\"Created\":1682935300,\"Ports\":[],\"Labels\":{},\"State\":\"running\",\"Status\":\"Up 10 minutes (unhealthy)\",\"HostConfig\":{\"NetworkMode\":\"bridge\"},\"NetworkSettings\":{\"Networks\":{}},\"Mounts\":[]}]".to_string()
+}
@@ -1,3 +1,5 @@
+use serde_json::{Map, Value};
+
 #[derive(Serialize, Deserialize, Debug)]
 #[allow(non_snake_case)]
 pub struct Version {
@@ -10,4 +12,6 @@ pub struct Version {
     pub KernelVersion: String,
     pub BuildTime: Option<String>,
     pub Experimental: Option<bool>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
 }
@@ -0,0 +1,111 @@
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[allow(non_snake_case)]
+pub struct Volume {
+    pub Name: String,
+    pub Driver: String,
+    pub Mountpoint: String,
+    pub CreatedAt: Option<String>,
+    pub Status: Option<HashMap<String, serde_json::Value>>,
+    pub Labels: Option<HashMap<String, String>>,
+    pub Scope: String,
+    pub Options: Option<HashMap<String, String>>,
+    pub UsageData: Option<UsageData>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl std::fmt::Display for Volume {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::result::Result<(), std::fmt::Error> {
+        write!(f, "{}", self.Name)
+    }
+}
+
+/// Disk usage of a volume, only filled in by the `/system/df` endpoint. Both
+/// values are `-1` when they are not available.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[allow(non_snake_case)]
+pub struct UsageData {
+    pub Size: i64,
+    pub RefCount: i64,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[allow(non_snake_case)]
+pub struct VolumeList {
+    pub Volumes: Option<Vec<Volume>>,
+    pub Warnings: Option<Vec<String>>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[allow(non_snake_case)]
+pub struct VolumeCreate {
+    pub Name: Option<String>,
+    pub Driver: Option<String>,
+    pub DriverOpts: Option<HashMap<String, String>>,
+    pub Labels: Option<HashMap<String, String>>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct VolumeListOptions {
+    pub dangling: Option<bool>,
+    pub driver: Vec<String>,
+    /// Labels given as `key` or `key=value`.
+    pub label: Vec<String>,
+    pub name: Vec<String>,
+}
+
+impl VolumeListOptions {
+    pub(crate) fn to_map(&self) -> HashMap<String, Vec<String>> {
+        let mut filters = HashMap::new();
+        if let Some(dangling) = self.dangling {
+            filters.insert("dangling".to_string(), vec![dangling.to_string()]);
+        }
+        if !self.driver.is_empty() {
+            filters.insert("driver".to_string(), self.driver.clone());
+        }
+        if !self.label.is_empty() {
+            filters.insert("label".to_string(), self.label.clone());
+        }
+        if !self.name.is_empty() {
+            filters.insert("name".to_string(), self.name.clone());
+        }
+        filters
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct VolumePruneFilters {
+    /// Only prune volumes with these labels, given as `key` or `key=value`.
+    pub label: Vec<String>,
+    /// Only prune volumes without these labels.
+    pub label_not: Vec<String>,
+}
+
+impl VolumePruneFilters {
+    pub(crate) fn to_map(&self) -> HashMap<String, Vec<String>> {
+        let mut filters = HashMap::new();
+        if !self.label.is_empty() {
+            filters.insert("label".to_string(), self.label.clone());
+        }
+        if !self.label_not.is_empty() {
+            filters.insert("label!".to_string(), self.label_not.clone());
+        }
+        filters
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[allow(non_snake_case)]
+pub struct VolumePrune {
+    pub VolumesDeleted: Option<Vec<String>>,
+    pub SpaceReclaimed: u64,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
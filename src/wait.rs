@@ -0,0 +1,86 @@
+use crate::container::Health;
+use serde_json::{Map, Value};
+use std::time::Duration;
+
+/// What `Docker::wait_container` waits for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WaitCondition {
+    /// Returns at once if the container is not running.
+    NotRunning,
+    /// Waits for the next exit, even if the container is not running yet.
+    NextExit,
+    Removed,
+}
+
+impl WaitCondition {
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            WaitCondition::NotRunning => "not-running",
+            WaitCondition::NextExit => "next-exit",
+            WaitCondition::Removed => "removed",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[allow(non_snake_case)]
+pub struct WaitResponse {
+    /// Exit code of the container.
+    pub StatusCode: i64,
+    pub Error: Option<WaitError>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[allow(non_snake_case)]
+pub struct WaitError {
+    pub Message: String,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// Error returned by `Docker::wait_healthy` when the container did not become
+/// healthy in time, carried inside the `std::io::Error` like `ApiError`.
+#[derive(Debug, Clone)]
+pub struct HealthTimeout {
+    pub timeout: Duration,
+    /// Health of the container when the wait gave up.
+    pub health: Health,
+}
+
+impl HealthTimeout {
+    pub fn from_io(error: &std::io::Error) -> Option<&HealthTimeout> {
+        error.get_ref()?.downcast_ref::<HealthTimeout>()
+    }
+}
+
+/// Number of healthcheck runs shown in the message.
+const LOG_ENTRIES: usize = 3;
+
+impl std::fmt::Display for HealthTimeout {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::result::Result<(), std::fmt::Error> {
+        write!(
+            f,
+            "container still {} after {:?}",
+            self.health.Status, self.timeout
+        )?;
+        for entry in self.health.last_probes(LOG_ENTRIES) {
+            write!(
+                f,
+                "\n  exit code {}: {}",
+                entry.ExitCode,
+                entry.Output.trim_end()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for HealthTimeout {}
+
+impl From<HealthTimeout> for std::io::Error {
+    fn from(error: HealthTimeout) -> Self {
+        std::io::Error::new(std::io::ErrorKind::TimedOut, error)
+    }
+}